@@ -0,0 +1,355 @@
+//! Bootstrap confidence intervals for ratings.
+//!
+//! The games are resampled with replacement many times and the ratings are
+//! recomputed for every sample. The spread of the resulting ratings and
+//! ranks gives error bars for a leaderboard.
+
+use bradley_terry::BradleyTerry;
+use {Elo, EloRanking, Error, ExpectationModel, Float, MatchResult, Outcome};
+
+/// The SplitMix64 generator, which is small and fully reproducible from its
+/// seed.
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Random {
+        return Random {
+            state: seed,
+        };
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        return z ^ (z >> 31);
+    }
+
+    /// A uniformly distributed integer below a bound.
+    fn below(&mut self, bound: usize) -> usize {
+        return ((self.next() as u128 * bound as u128) >> 64) as usize;
+    }
+}
+
+/// A player rated by `Bootstrap::elo`.
+struct Player<F> {
+    rating: F,
+}
+
+impl<F: Float> Elo<F> for Player<F> {
+    fn get_rating(&self) -> F {
+        return self.rating;
+    }
+    fn change_rating(&mut self, rating: F) {
+        self.rating = self.rating + rating;
+    }
+}
+
+/// The bootstrap distribution of the rating and rank of one player.
+#[derive(Clone, Debug)]
+pub struct Interval {
+    /// The median rating.
+    pub median: f64,
+    /// The lower bound of the rating interval.
+    pub lower: f64,
+    /// The upper bound of the rating interval.
+    pub upper: f64,
+    /// The median rank, where 1 is the highest rated player.
+    pub rank: usize,
+    /// The best rank within the interval.
+    pub rank_lower: usize,
+    /// The worst rank within the interval.
+    pub rank_upper: usize,
+}
+
+/// Percentile of sorted values, interpolating between neighbours.
+fn percentile(sorted: &[f64], fraction: f64) -> f64 {
+    let position = fraction * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    return sorted[below] + (sorted[above] - sorted[below]) *
+        (position - below as f64);
+}
+
+/// Bootstrap.
+pub struct Bootstrap {
+    rounds: usize,
+    seed: u64,
+    confidence: f64,
+}
+
+impl Bootstrap {
+    /// Create a new bootstrap with a number of resampling rounds and the
+    /// seed of the random number generator.
+    ///
+    /// Intervals cover 95% of the bootstrap distribution by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::bootstrap::Bootstrap;
+    /// let bootstrap = Bootstrap::new(1000, 42);
+    /// ```
+    pub fn new(rounds: usize, seed: u64) -> Bootstrap {
+        return Bootstrap {
+            rounds,
+            seed,
+            confidence: 0.95,
+        }
+    }
+
+    /// Change the number of resampling rounds.
+    pub fn set_rounds(&mut self, rounds: usize) {
+        self.rounds = rounds;
+    }
+
+    /// Returns the number of resampling rounds.
+    pub fn get_rounds(&self) -> usize {
+        return self.rounds;
+    }
+
+    /// Change the seed of the random number generator.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the seed of the random number generator.
+    pub fn get_seed(&self) -> u64 {
+        return self.seed;
+    }
+
+    /// Change the confidence level of the intervals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::bootstrap::Bootstrap;
+    /// # let mut bootstrap = Bootstrap::new(1000, 42);
+    /// bootstrap.set_confidence(0.9);
+    /// assert_eq!(0.9, bootstrap.get_confidence());
+    /// ```
+    pub fn set_confidence(&mut self, confidence: f64) {
+        self.confidence = confidence;
+    }
+
+    /// Returns the confidence level of the intervals.
+    pub fn get_confidence(&self) -> f64 {
+        return self.confidence;
+    }
+
+    /// Compute intervals with any rating method.
+    ///
+    /// Each game is given as the indices of the two players and the score of
+    /// the first one. Every round passes a resampled list of games, in their
+    /// original order, to `rate`, which returns the rating of every player.
+    /// Without any rounds there are no intervals. Returns an error if a
+    /// game is invalid, with the rules of `MatchResult::validate`.
+    pub fn run<F>(&self,
+                  players: usize,
+                  games: &[(usize, usize, f64)],
+                  mut rate: F) -> Result<Vec<Interval>, Error>
+        where F: FnMut(&[(usize, usize, f64)]) -> Vec<f64> {
+        for &(one, two, score) in games {
+            MatchResult::new(one, two, Outcome::Partial(score)).validate(players)?;
+        }
+        if self.rounds == 0 {
+            return Ok(Vec::new());
+        }
+        let mut random = Random::new(self.seed);
+        let mut ratings = vec![Vec::with_capacity(self.rounds); players];
+        let mut ranks = vec![Vec::with_capacity(self.rounds); players];
+        let mut indices = vec![0; games.len()];
+        let mut sample = Vec::with_capacity(games.len());
+        for _ in 0..self.rounds {
+            for index in indices.iter_mut() {
+                *index = random.below(games.len());
+            }
+            indices.sort();
+            sample.clear();
+            sample.extend(indices.iter().map(|&index| games[index]));
+            let round = rate(&sample);
+            for (player, &rating) in round.iter().enumerate() {
+                ratings[player].push(rating);
+                ranks[player].push(
+                    1 + round.iter().filter(|&&other| other > rating).count()
+                );
+            }
+        }
+        let tail = (1.0 - self.confidence) / 2.0;
+        return Ok(ratings.iter_mut()
+            .zip(ranks.iter_mut())
+            .map(|(ratings, ranks)| {
+                ratings.sort_by(|a, b| a.total_cmp(b));
+                ranks.sort();
+                let last = (ranks.len() - 1) as f64;
+                Interval {
+                    median: percentile(ratings, 0.5),
+                    lower: percentile(ratings, tail),
+                    upper: percentile(ratings, 1.0 - tail),
+                    rank: ranks[(0.5 * last).round() as usize],
+                    rank_lower: ranks[(tail * last).floor() as usize],
+                    rank_upper: ranks[((1.0 - tail) * last).ceil() as usize],
+                }
+            })
+            .collect());
+    }
+
+    /// Compute intervals for sequential Elo ratings.
+    ///
+    /// Every player starts each round at the initial rating, and every game
+    /// is rated with its actual score. Returns an error if a game is
+    /// invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// # use elo::bootstrap::Bootstrap;
+    /// let games = [(0, 1, 1.0), (0, 2, 1.0), (1, 2, 0.5), (2, 1, 0.0)];
+    /// let bootstrap = Bootstrap::new(200, 7);
+    /// let intervals = bootstrap.elo(&EloRanking::new(32), 3, 1500.0, &games).unwrap();
+    /// assert!(intervals[0].lower <= intervals[0].median);
+    /// ```
+    pub fn elo<F, M>(&self,
+                     ranking: &EloRanking<F, M>,
+                     players: usize,
+                     initial_rating: F,
+                     games: &[(usize, usize, f64)]) -> Result<Vec<Interval>, Error>
+        where F: Float, M: ExpectationModel<F> {
+        return self.run(players, games, |sample| {
+            let mut rated: Vec<Player<F>> = (0..players)
+                .map(|_| Player { rating: initial_rating })
+                .collect();
+            for &(one, two, score) in sample {
+                let result = MatchResult::new(one, two, Outcome::Partial(F::from_f64(score)));
+                // The games were validated by `run`.
+                ranking.apply(&mut rated, &result).unwrap();
+            }
+            rated.iter().map(|player| player.rating.to_f64()).collect()
+        });
+    }
+
+    /// Compute intervals for a Bradley-Terry fit. Returns an error if a
+    /// game is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::bradley_terry::BradleyTerry;
+    /// # use elo::bootstrap::Bootstrap;
+    /// let games = [(0, 1, 1.0), (0, 2, 1.0), (1, 2, 0.5), (2, 1, 0.0)];
+    /// let mut bradley_terry = BradleyTerry::default();
+    /// bradley_terry.set_regularization(0.1);
+    /// let bootstrap = Bootstrap::new(200, 7);
+    /// let intervals = bootstrap.bradley_terry(&bradley_terry, 3, &games).unwrap();
+    /// assert_eq!(1, intervals[0].rank_lower);
+    /// ```
+    pub fn bradley_terry(&self,
+                         fitter: &BradleyTerry,
+                         players: usize,
+                         games: &[(usize, usize, f64)]) -> Result<Vec<Interval>, Error> {
+        return self.run(players, games, |sample| {
+            fitter.fit(players, sample).ratings
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn games() -> Vec<(usize, usize, f64)> {
+        let mut games = Vec::new();
+        for _ in 0..10 {
+            games.push((0, 1, 1.0));
+            games.push((0, 2, 1.0));
+            games.push((1, 2, 1.0));
+            games.push((2, 1, 0.5));
+        }
+        games.push((1, 0, 1.0));
+        return games;
+    }
+
+    #[test]
+    fn reproducible() {
+        let games = games();
+        let ranking = EloRanking::new(16);
+        let first = Bootstrap::new(100, 1).elo(&ranking, 3, 1500.0, &games).unwrap();
+        let second = Bootstrap::new(100, 1).elo(&ranking, 3, 1500.0, &games).unwrap();
+        let other = Bootstrap::new(100, 2).elo(&ranking, 3, 1500.0, &games).unwrap();
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.median, b.median);
+            assert_eq!(a.lower, b.lower);
+            assert_eq!(a.upper, b.upper);
+        }
+        assert!(first.iter().zip(other.iter()).any(|(a, b)| a.lower != b.lower));
+    }
+
+    #[test]
+    fn intervals() {
+        let games = games();
+        let mut fitter = BradleyTerry::default();
+        fitter.set_regularization(0.01);
+        let intervals = Bootstrap::new(200, 3).bradley_terry(&fitter, 3, &games).unwrap();
+        for interval in &intervals {
+            assert!(interval.lower <= interval.median);
+            assert!(interval.median <= interval.upper);
+            assert!(interval.rank_lower <= interval.rank);
+            assert!(interval.rank <= interval.rank_upper);
+        }
+        assert_eq!(1, intervals[0].rank);
+        assert_eq!(3, intervals[2].rank);
+        assert!(intervals[0].lower > intervals[2].upper);
+    }
+
+    #[test]
+    fn percentiles() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(3.0, percentile(&sorted, 0.5));
+        assert_eq!(1.5, percentile(&sorted, 0.125));
+        assert_eq!(5.0, percentile(&sorted, 1.0));
+    }
+
+    #[test]
+    fn undefined_ratings() {
+        // A rating method may return NaN for some samples.
+        let games = games();
+        let intervals = Bootstrap::new(20, 5).run(3, &games, |sample| {
+            vec![sample.len() as f64, f64::NAN, 0.0]
+        }).unwrap();
+        assert_eq!(games.len() as f64, intervals[0].median);
+        assert!(intervals[1].median.is_nan());
+    }
+
+    #[test]
+    fn no_rounds() {
+        let ranking = EloRanking::new(16);
+        assert!(Bootstrap::new(0, 1).elo(&ranking, 3, 1500.0, &games()).unwrap()
+                .is_empty());
+    }
+
+    #[test]
+    fn partial_scores() {
+        // A score of 0.75 is rated as such, not rounded to a win.
+        let games = [(0, 1, 0.75)];
+        let ranking = EloRanking::new(32);
+        let intervals = Bootstrap::new(10, 1).elo(&ranking, 2, 1500.0, &games).unwrap();
+        assert_eq!(1508.0, intervals[0].median);
+        assert_eq!(1492.0, intervals[1].median);
+    }
+
+    #[test]
+    fn invalid_games() {
+        let games = [(0, 1, 1.0), (0, 5, 1.0)];
+        let bootstrap = Bootstrap::new(10, 1);
+        assert_eq!(Some(Error::UnknownPlayer(5)),
+                   bootstrap.elo(&EloRanking::new(32), 2, 1500.0, &games).err());
+        assert_eq!(Some(Error::UnknownPlayer(5)),
+                   bootstrap.bradley_terry(&BradleyTerry::default(), 2, &games).err());
+        assert_eq!(Some(Error::InvalidScore(1.5)),
+                   bootstrap.run(2, &[(0, 1, 1.5)], |_| vec![0.0, 0.0]).err());
+    }
+}
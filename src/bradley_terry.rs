@@ -0,0 +1,494 @@
+//! Maximum likelihood Bradley-Terry ratings.
+//!
+//! Unlike the sequential updates of `EloRanking`, the fit uses every game at
+//! once, so the result does not depend on the order of the games. Ratings
+//! are on the same logistic scale as Elo: a difference of 400 points means
+//! odds of 10 to 1.
+
+use std::f64::consts::LN_10;
+
+use linear::solve;
+
+/// Conversion factor from natural log-strengths to rating points.
+pub(crate) const SCALE: f64 = 400.0 / LN_10;
+
+/// The iteration used to maximize the likelihood.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    /// Hunter's minorization-maximization algorithm. Every iteration is
+    /// cheap, but many may be needed.
+    MinorizationMaximization,
+    /// Newton's method on the log-likelihood. Converges in few iterations,
+    /// each of which solves a linear system in the number of players.
+    Newton,
+}
+
+/// The result of fitting a Bradley-Terry model.
+#[derive(Clone, Debug)]
+pub struct Fit {
+    /// The rating of every player.
+    pub ratings: Vec<f64>,
+    /// The number of iterations performed.
+    pub iterations: usize,
+    /// Whether the change in ratings fell below the tolerance.
+    pub converged: bool,
+}
+
+/// Sufficient statistics of a list of games.
+struct Summary {
+    /// Total score of every player.
+    wins: Vec<f64>,
+    /// Number of games between every pair of players.
+    games: Vec<Vec<f64>>,
+    /// Whether every player has played.
+    played: Vec<bool>,
+    /// The first player of the connected component of every player.
+    component: Vec<usize>,
+}
+
+impl Summary {
+    fn new(players: usize, games: &[(usize, usize, f64)]) -> Summary {
+        let mut summary = Summary {
+            wins: vec![0.0; players],
+            games: vec![vec![0.0; players]; players],
+            played: Vec::new(),
+            component: (0..players).collect(),
+        };
+        for &(one, two, score) in games {
+            summary.wins[one] += score;
+            summary.wins[two] += 1.0 - score;
+            summary.games[one][two] += 1.0;
+            summary.games[two][one] += 1.0;
+        }
+        summary.played = summary.games.iter()
+            .map(|games| games.iter().any(|&n| n > 0.0))
+            .collect();
+        // Players are visited in order, so every component is labelled by
+        // its first player.
+        for first in 0..players {
+            if summary.component[first] != first {
+                continue;
+            }
+            let mut stack = vec![first];
+            while let Some(i) = stack.pop() {
+                for j in 0..players {
+                    if summary.games[i][j] > 0.0 && j != first &&
+                        summary.component[j] == j {
+                        summary.component[j] = first;
+                        stack.push(j);
+                    }
+                }
+            }
+        }
+        return summary;
+    }
+}
+
+/// The probability that the first player wins, from natural log-strengths.
+pub(crate) fn probability(theta_one: f64, theta_two: f64) -> f64 {
+    return 1.0 / (1.0 + (theta_two - theta_one).exp());
+}
+
+/// BradleyTerry.
+pub struct BradleyTerry {
+    method: Method,
+    max_iterations: usize,
+    tolerance: f64,
+    regularization: f64,
+    mean_rating: f64,
+}
+
+impl Default for BradleyTerry {
+    fn default() -> BradleyTerry {
+        return BradleyTerry::new(Method::Newton);
+    }
+}
+
+impl BradleyTerry {
+    /// Create a new Bradley-Terry fitter.
+    ///
+    /// By default the fit runs for at most 1000 iterations, stops once no
+    /// rating changes by more than 1e-6 points, is not regularized and
+    /// centers the ratings on 1500.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::bradley_terry::{BradleyTerry, Method};
+    /// let bradley_terry = BradleyTerry::new(Method::MinorizationMaximization);
+    /// ```
+    pub fn new(method: Method) -> BradleyTerry {
+        return BradleyTerry {
+            method,
+            max_iterations: 1000,
+            tolerance: 0.000001,
+            regularization: 0.0,
+            mean_rating: 1500.0,
+        }
+    }
+
+    /// Change the iteration method.
+    pub fn set_method(&mut self, method: Method) {
+        self.method = method;
+    }
+
+    /// Returns the iteration method.
+    pub fn get_method(&self) -> Method {
+        return self.method;
+    }
+
+    /// Change the maximum number of iterations.
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    /// Returns the maximum number of iterations.
+    pub fn get_max_iterations(&self) -> usize {
+        return self.max_iterations;
+    }
+
+    /// Change the convergence tolerance, in rating points.
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    /// Returns the convergence tolerance.
+    pub fn get_tolerance(&self) -> f64 {
+        return self.tolerance;
+    }
+
+    /// Change the strength of the L2 penalty on the natural log-strengths.
+    ///
+    /// Without regularization the maximum likelihood estimate only exists if
+    /// every player both won and lost (or drew) against players connected to
+    /// the rest of the pool. A small penalty, such as 0.01, keeps the ratings
+    /// of undefeated and winless players finite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::bradley_terry::BradleyTerry;
+    /// # let mut bradley_terry = BradleyTerry::default();
+    /// bradley_terry.set_regularization(0.01);
+    /// assert_eq!(0.01, bradley_terry.get_regularization());
+    /// ```
+    pub fn set_regularization(&mut self, regularization: f64) {
+        self.regularization = regularization;
+    }
+
+    /// Returns the strength of the L2 penalty.
+    pub fn get_regularization(&self) -> f64 {
+        return self.regularization;
+    }
+
+    /// Change the mean of the fitted ratings.
+    pub fn set_mean_rating(&mut self, mean_rating: f64) {
+        self.mean_rating = mean_rating;
+    }
+
+    /// Returns the mean of the fitted ratings.
+    pub fn get_mean_rating(&self) -> f64 {
+        return self.mean_rating;
+    }
+
+    /// Internal method for one minorization-maximization iteration, or `None`
+    /// if a rating diverges.
+    fn mm_step(&self, summary: &Summary, theta: &[f64]) -> Option<Vec<f64>> {
+        let played = &summary.played;
+        let mut next: Vec<f64> = theta.iter()
+            .enumerate()
+            .map(|(i, &theta_i)| {
+                if !played[i] {
+                    return theta_i;
+                }
+                let gamma_i = theta_i.exp();
+                let a: f64 = theta.iter()
+                    .zip(summary.games[i].iter())
+                    .map(|(theta_j, n)| n / (gamma_i + theta_j.exp()))
+                    .sum();
+                let wins = summary.wins[i];
+                if self.regularization == 0.0 {
+                    return (wins / a).ln();
+                }
+                // Maximize wins * x - a * e^x - regularization * x^2 / 2.
+                let mut x = theta_i;
+                for _ in 0..50 {
+                    let gradient = wins - a * x.exp() - self.regularization * x;
+                    let step = gradient / (a * x.exp() + self.regularization);
+                    x += step;
+                    if step.abs() < 1e-12 {
+                        break;
+                    }
+                }
+                return x;
+            })
+            .collect();
+        // A winless player has no finite rating without a penalty.
+        if !next.iter().all(|value| value.is_finite()) {
+            return None;
+        }
+        let count = played.iter().filter(|&&played| played).count();
+        if self.regularization == 0.0 && count > 0 {
+            // Players without games keep their rating and do not count
+            // towards the mean.
+            let mean = next.iter()
+                .zip(played.iter())
+                .filter(|&(_, &played)| played)
+                .map(|(value, _)| value)
+                .sum::<f64>() / count as f64;
+            for (value, &played) in next.iter_mut().zip(played.iter()) {
+                if played {
+                    *value -= mean;
+                }
+            }
+        }
+        return Some(next);
+    }
+
+    /// Internal method for the penalized log-likelihood.
+    fn log_likelihood(&self, summary: &Summary, theta: &[f64]) -> f64 {
+        let mut total = 0.0;
+        for (i, &theta_i) in theta.iter().enumerate() {
+            total += summary.wins[i] * theta_i -
+                self.regularization * theta_i * theta_i / 2.0;
+            for (j, &theta_j) in theta.iter().enumerate().skip(i + 1) {
+                let n = summary.games[i][j];
+                if n > 0.0 {
+                    let larger = theta_i.max(theta_j);
+                    let smaller = theta_i.min(theta_j);
+                    total -= n * (larger + (smaller - larger).exp().ln_1p());
+                }
+            }
+        }
+        return total;
+    }
+
+    /// Internal method for one Newton iteration, or `None` if the Hessian is
+    /// singular.
+    fn newton_step(&self, summary: &Summary, theta: &[f64]) -> Option<Vec<f64>> {
+        let players = theta.len();
+        let mut gradient: Vec<f64> = summary.wins.iter()
+            .zip(theta.iter())
+            .map(|(wins, theta_i)| wins - self.regularization * theta_i)
+            .collect();
+        let mut hessian = vec![vec![0.0; players]; players];
+        for i in 0..players {
+            hessian[i][i] = self.regularization;
+            for j in 0..players {
+                let n = summary.games[i][j];
+                if i == j || n == 0.0 {
+                    continue;
+                }
+                let p = probability(theta[i], theta[j]);
+                gradient[i] -= n * p;
+                hessian[i][i] += n * p * (1.0 - p);
+                hessian[i][j] -= n * p * (1.0 - p);
+            }
+        }
+        // Players without games are left out. Without a penalty the
+        // likelihood only depends on rating differences within each
+        // connected component, so its first player is held fixed.
+        let free: Vec<usize> = (0..players)
+            .filter(|&i| {
+                if self.regularization == 0.0 {
+                    summary.played[i] && summary.component[i] != i
+                } else {
+                    summary.played[i]
+                }
+            })
+            .collect();
+        let reduced: Vec<Vec<f64>> = free.iter()
+            .map(|&i| free.iter().map(|&j| hessian[i][j]).collect())
+            .collect();
+        let (step, _) = solve(reduced, free.iter().map(|&i| gradient[i]).collect());
+        if !step.iter().all(|delta| delta.is_finite()) {
+            return None;
+        }
+        let current = self.log_likelihood(summary, theta);
+        let mut scale = 1.0;
+        loop {
+            let mut next = theta.to_vec();
+            for (&i, delta) in free.iter().zip(step.iter()) {
+                next[i] += scale * delta;
+            }
+            if scale < 1e-6 ||
+                self.log_likelihood(summary, &next) >= current {
+                return Some(next);
+            }
+            scale /= 2.0;
+        }
+    }
+
+    /// Fit ratings to a list of games.
+    ///
+    /// Each game is given as the indices of the two players and the score of
+    /// the first one, so ties are a score of 0.5.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::bradley_terry::BradleyTerry;
+    /// let bradley_terry = BradleyTerry::default();
+    /// // Player 0 scores 3 out of 4 against player 1.
+    /// let games = [(0, 1, 1.0), (0, 1, 1.0), (0, 1, 0.5), (1, 0, 0.5)];
+    /// let fit = bradley_terry.fit(2, &games);
+    /// assert!(fit.converged);
+    /// assert!((fit.ratings[0] - fit.ratings[1] - 190.85).abs() < 0.01);
+    /// ```
+    pub fn fit(&self, players: usize, games: &[(usize, usize, f64)]) -> Fit {
+        if players == 0 {
+            return Fit {
+                ratings: Vec::new(),
+                iterations: 0,
+                converged: true,
+            };
+        }
+        let summary = Summary::new(players, games);
+        let mut theta = vec![0.0; players];
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < self.max_iterations && !converged {
+            let next = match self.method {
+                Method::MinorizationMaximization => {
+                    match self.mm_step(&summary, &theta) {
+                        Some(next) => next,
+                        None => break,
+                    }
+                },
+                Method::Newton => match self.newton_step(&summary, &theta) {
+                    Some(next) => next,
+                    None => break,
+                },
+            };
+            converged = next.iter()
+                .zip(theta.iter())
+                .all(|(a, b)| ((a - b) * SCALE).abs() <= self.tolerance);
+            theta = next;
+            iterations += 1;
+        }
+        // Players without games are rated on the mean.
+        let count = summary.played.iter().filter(|&&played| played).count();
+        let mean = theta.iter()
+            .zip(summary.played.iter())
+            .filter(|&(_, &played)| played)
+            .map(|(value, _)| value)
+            .sum::<f64>() / count.max(1) as f64;
+        return Fit {
+            ratings: theta.iter()
+                .zip(summary.played.iter())
+                .map(|(value, &played)| {
+                    if played {
+                        (value - mean) * SCALE + self.mean_rating
+                    } else {
+                        self.mean_rating
+                    }
+                })
+                .collect(),
+            iterations,
+            converged,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: [(usize, usize, f64); 9] = [
+        (0, 1, 1.0), (0, 1, 0.0), (0, 1, 1.0),
+        (1, 2, 1.0), (1, 2, 0.5), (2, 1, 1.0),
+        (2, 0, 1.0), (0, 2, 1.0), (0, 2, 0.5),
+    ];
+
+    #[test]
+    fn methods_agree() {
+        let newton = BradleyTerry::new(Method::Newton).fit(3, &GAMES);
+        let mm = BradleyTerry::new(Method::MinorizationMaximization)
+            .fit(3, &GAMES);
+        assert!(newton.converged && mm.converged);
+        assert!(newton.iterations < mm.iterations);
+        for (a, b) in newton.ratings.iter().zip(mm.ratings.iter()) {
+            assert!((a - b).abs() < 0.001);
+        }
+        let mean = newton.ratings.iter().sum::<f64>() / 3.0;
+        assert!((mean - 1500.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn likelihood_equations() {
+        // At the maximum every player's expected score equals their score.
+        let fit = BradleyTerry::default().fit(3, &GAMES);
+        let mut expected = [0.0; 3];
+        let mut actual = [0.0; 3];
+        for &(one, two, score) in GAMES.iter() {
+            let e = 1.0 / (1.0 + 10f64.powf(
+                (fit.ratings[two] - fit.ratings[one]) / 400.0
+            ));
+            expected[one] += e;
+            expected[two] += 1.0 - e;
+            actual[one] += score;
+            actual[two] += 1.0 - score;
+        }
+        for (e, a) in expected.iter().zip(actual.iter()) {
+            assert!((e - a).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn regularization() {
+        // An undefeated player has no finite maximum likelihood rating.
+        let games = [(0, 1, 1.0), (0, 1, 1.0), (1, 2, 0.5)];
+        for &method in &[Method::Newton, Method::MinorizationMaximization] {
+            let mut bradley_terry = BradleyTerry::new(method);
+            bradley_terry.set_max_iterations(100);
+            assert!(!bradley_terry.fit(3, &games).converged);
+            bradley_terry.set_regularization(0.1);
+            bradley_terry.set_max_iterations(10000);
+            let fit = bradley_terry.fit(3, &games);
+            assert!(fit.converged);
+            assert!(fit.ratings[0] > fit.ratings[1]);
+            assert!(fit.ratings.iter().all(|rating| rating.is_finite()));
+        }
+    }
+
+    #[test]
+    fn winless_players() {
+        // Player 2 lost every game, so only a penalty keeps its rating
+        // finite; the fit stops instead of returning NaN.
+        let games = [(0, 1, 1.0), (1, 2, 1.0), (0, 2, 1.0), (1, 0, 1.0)];
+        for &method in &[Method::Newton, Method::MinorizationMaximization] {
+            let fit = BradleyTerry::new(method).fit(3, &games);
+            assert!(!fit.converged);
+            assert!(fit.ratings.iter().all(|rating| rating.is_finite()));
+        }
+    }
+
+    #[test]
+    fn players_without_games() {
+        // Player 3 never played: it does not change the other ratings and
+        // stays on the mean.
+        for &method in &[Method::Newton, Method::MinorizationMaximization] {
+            let three = BradleyTerry::new(method).fit(3, &GAMES);
+            let four = BradleyTerry::new(method).fit(4, &GAMES);
+            assert!(four.converged);
+            for (a, b) in three.ratings.iter().zip(four.ratings.iter()) {
+                assert!((a - b).abs() < 1e-6);
+            }
+            assert!((four.ratings[3] - 1500.0).abs() < 1e-6);
+        }
+        let empty = BradleyTerry::default().fit(0, &[]);
+        assert!(empty.ratings.is_empty());
+    }
+
+    #[test]
+    fn disconnected_pools() {
+        // Two pools that never met are each fitted on their own.
+        let games = [(0, 2, 1.0), (2, 0, 1.0), (2, 0, 1.0),
+                     (1, 3, 1.0), (1, 3, 1.0), (3, 1, 1.0)];
+        let fit = BradleyTerry::new(Method::Newton).fit(4, &games);
+        assert!(fit.converged);
+        let difference = 400.0 * 2f64.log10();
+        assert!((fit.ratings[2] - fit.ratings[0] - difference).abs() < 1e-6);
+        assert!((fit.ratings[1] - fit.ratings[3] - difference).abs() < 1e-6);
+    }
+}
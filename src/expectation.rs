@@ -0,0 +1,239 @@
+//! Models of the expected score for a rating difference.
+
+use float::Float;
+use normal::cdf;
+
+/// ExpectationModel.
+///
+/// Maps the rating difference between a player and an opponent to the
+/// expected score of the player. The curve should increase from 0 to 1 and
+/// return 0.5 for equal ratings.
+pub trait ExpectationModel<F: Float> {
+    /// Returns the expected score of a player rated `difference` points above
+    /// the opponent.
+    fn expected_score(&self, difference: F) -> F;
+
+    /// Returns the rating difference at which a player is expected to score
+    /// `expected`, the inverse of `expected_score`.
+    ///
+    /// An expected score of 1 is an infinite difference and 0 a negative
+    /// infinite one, and scores outside of [0, 1] have no difference, so they
+    /// give NaN. The default solves `expected_score` numerically.
+    fn rating_difference(&self, expected: F) -> F {
+        if let Some(difference) = limit(expected) {
+            return difference;
+        }
+        return solve(|difference| self.expected_score(difference), expected,
+                     F::from_f64(-400.0), F::from_f64(400.0));
+    }
+}
+
+/// Find where an increasing function reaches a target by bisection, first
+/// widening the bracket from `lower` to `upper` until it contains it.
+pub fn solve<F: Float, G: Fn(F) -> F>(function: G, target: F, mut lower: F, mut upper: F) -> F {
+    let two = F::from_f64(2.0);
+    let mut step = F::from_f64(100.0);
+    while function(lower) > target {
+        lower = lower - step;
+        step = step * two;
+    }
+    let mut step = F::from_f64(100.0);
+    while function(upper) < target {
+        upper = upper + step;
+        step = step * two;
+    }
+    for _ in 0..100 {
+        let middle = (lower + upper) / two;
+        if function(middle) < target {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    return (lower + upper) / two;
+}
+
+/// The rating difference for the expected scores 0 and 1 and for invalid
+/// ones, or `None` for the others.
+fn limit<F: Float>(expected: F) -> Option<F> {
+    if expected > F::from_f64(0.0) && expected < F::from_f64(1.0) {
+        return None;
+    }
+    if expected == F::from_f64(1.0) {
+        return Some(F::from_f64(f64::INFINITY));
+    }
+    if expected == F::from_f64(0.0) {
+        return Some(F::from_f64(f64::NEG_INFINITY));
+    }
+    return Some(F::from_f64(f64::NAN));
+}
+
+/// Logistic.
+///
+/// The logistic curve of the Elo system as used by FIDE and most online
+/// ratings: a player rated `scale` points above the opponent is expected to
+/// score `base` times as much.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Logistic<F> {
+    base: F,
+    scale: F,
+}
+
+impl<F: Float> Logistic<F> {
+    /// Create a logistic curve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Logistic;
+    /// let natural = Logistic::new(std::f64::consts::E, 173.7178);
+    /// ```
+    pub fn new(base: F, scale: F) -> Logistic<F> {
+        return Logistic {
+            base,
+            scale,
+        }
+    }
+
+    /// Change the base.
+    pub fn set_base(&mut self, base: F) {
+        self.base = base;
+    }
+
+    /// Returns the base.
+    pub fn get_base(&self) -> F {
+        return self.base;
+    }
+
+    /// Change the scale.
+    pub fn set_scale(&mut self, scale: F) {
+        self.scale = scale;
+    }
+
+    /// Returns the scale.
+    pub fn get_scale(&self) -> F {
+        return self.scale;
+    }
+}
+
+impl<F: Float> Default for Logistic<F> {
+    /// Base 10 and a scale of 400.
+    fn default() -> Logistic<F> {
+        return Logistic::new(F::from_f64(10.0), F::from_f64(400.0));
+    }
+}
+
+impl<F: Float> ExpectationModel<F> for Logistic<F> {
+    fn expected_score(&self, difference: F) -> F {
+        return F::from_f64(1.0) / (F::from_f64(1.0) +
+                                   self.base.powf(-difference / self.scale));
+    }
+
+    fn rating_difference(&self, expected: F) -> F {
+        if let Some(difference) = limit(expected) {
+            return difference;
+        }
+        return self.scale * (expected / (F::from_f64(1.0) - expected)).ln() /
+            self.base.ln();
+    }
+}
+
+/// Normal.
+///
+/// The normal distribution curve of Arpad Elo's original formulation, where
+/// the expected score is the probability that a normally distributed
+/// performance difference favours the player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normal<F> {
+    deviation: F,
+}
+
+impl<F: Float> Normal<F> {
+    /// Create a normal curve with the standard deviation of the performance
+    /// difference.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Normal;
+    /// let normal = Normal::new(200.0 * 2f64.sqrt());
+    /// ```
+    pub fn new(deviation: F) -> Normal<F> {
+        return Normal {
+            deviation,
+        }
+    }
+
+    /// Change the standard deviation of the performance difference.
+    pub fn set_deviation(&mut self, deviation: F) {
+        self.deviation = deviation;
+    }
+
+    /// Returns the standard deviation of the performance difference.
+    pub fn get_deviation(&self) -> F {
+        return self.deviation;
+    }
+}
+
+impl<F: Float> Default for Normal<F> {
+    /// Elo's performance deviation of 200 for each player, so 200√2 for the
+    /// difference.
+    fn default() -> Normal<F> {
+        return Normal::new(F::from_f64(200.0 * ::std::f64::consts::SQRT_2));
+    }
+}
+
+impl<F: Float> ExpectationModel<F> for Normal<F> {
+    fn expected_score(&self, difference: F) -> F {
+        return F::from_f64(cdf((difference / self.deviation).to_f64()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves() {
+        let logistic = Logistic::<f64>::default();
+        let normal = Normal::<f64>::default();
+        for &model in &[&logistic as &dyn ExpectationModel<f64>, &normal] {
+            assert!((model.expected_score(0.0) - 0.5).abs() < 1e-7);
+            assert!((model.expected_score(150.0) +
+                     model.expected_score(-150.0) - 1.0).abs() < 1e-7);
+        }
+        assert!((logistic.expected_score(400.0) - 10.0 / 11.0).abs() < 1e-12);
+        // One standard deviation of the difference.
+        assert!((normal.expected_score(282.842712) - 0.8413447).abs() < 1e-6);
+    }
+
+    /// Expected score rising linearly over 800 points.
+    struct Linear;
+
+    impl ExpectationModel<f64> for Linear {
+        fn expected_score(&self, difference: f64) -> f64 {
+            return (0.5 + difference / 800.0).clamp(0.0, 1.0);
+        }
+    }
+
+    #[test]
+    fn inverses() {
+        let logistic = Logistic::<f64>::default();
+        let normal = Normal::<f64>::default();
+        for &model in &[&logistic as &dyn ExpectationModel<f64>, &normal, &Linear] {
+            // The normal curve is only accurate to about 1e-7.
+            assert!(model.rating_difference(0.5).abs() < 1e-3);
+            for &difference in &[-350.0, -20.0, 1.0, 191.0] {
+                let expected = model.expected_score(difference);
+                assert!((model.rating_difference(expected) - difference).abs()
+                        < 1e-6);
+            }
+            assert_eq!(f64::INFINITY, model.rating_difference(1.0));
+            assert_eq!(f64::NEG_INFINITY, model.rating_difference(0.0));
+            assert!(model.rating_difference(1.5).is_nan());
+        }
+        // A score of 75% is 191 points.
+        assert!((logistic.rating_difference(0.75) - 190.85).abs() < 0.01);
+        assert!((Linear.rating_difference(0.75) - 200.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,327 @@
+//! The FIFA men's world ranking.
+//!
+//! Since 2018 FIFA ranks national teams with the "SUM" method, an Elo system
+//! where a team gains P = I × (W − We) points in a match. I is the
+//! importance of the match, W the result and We the expected result, on a
+//! logistic curve with a scale of 600 points and no home advantage. A team
+//! winning a penalty shootout scores 0.75 and the loser 0.5, and teams do not
+//! lose points in the knockout stages of final competitions.
+
+use {Elo, EloRanking, Error, Float, MatchResult, Outcome, Side};
+
+/// The importance of a match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Importance {
+    /// Friendly matches outside of the international match calendar: 5.
+    FriendlyOutsideWindow,
+    /// Friendly matches within the international match calendar: 10.
+    Friendly,
+    /// Nations League group stage matches: 15.
+    NationsLeagueGroup,
+    /// Nations League play-off and finals matches: 25.
+    NationsLeagueFinals,
+    /// Qualifiers for the World Cup and confederation final competitions: 25.
+    Qualifier,
+    /// Confederation final competition matches before the quarter-finals: 35.
+    ConfederationFinals,
+    /// Confederation final competition matches from the quarter-finals: 40.
+    ConfederationQuarterFinals,
+    /// World Cup matches before the quarter-finals: 50.
+    WorldCup,
+    /// World Cup matches from the quarter-finals: 60.
+    WorldCupQuarterFinals,
+}
+
+impl Importance {
+    /// Returns the importance coefficient I.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::fifa::Importance;
+    /// assert_eq!(60.0, Importance::WorldCupQuarterFinals.coefficient());
+    /// ```
+    pub fn coefficient(&self) -> f64 {
+        return match *self {
+            Importance::FriendlyOutsideWindow => 5.0,
+            Importance::Friendly => 10.0,
+            Importance::NationsLeagueGroup => 15.0,
+            Importance::NationsLeagueFinals => 25.0,
+            Importance::Qualifier => 25.0,
+            Importance::ConfederationFinals => 35.0,
+            Importance::ConfederationQuarterFinals => 40.0,
+            Importance::WorldCup => 50.0,
+            Importance::WorldCupQuarterFinals => 60.0,
+        };
+    }
+}
+
+/// How a match was played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fixture {
+    /// The importance of the match.
+    pub importance: Importance,
+    /// Whether the match is in the knockout stage of a final competition,
+    /// where teams do not lose points.
+    pub knockout: bool,
+    /// The winner of the penalty shootout of a drawn match, if any.
+    pub shootout_winner: Option<Side>,
+}
+
+impl Fixture {
+    /// Create a fixture outside of a knockout stage and without a shootout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Side;
+    /// # use elo::fifa::{Fixture, Importance};
+    /// let mut fixture = Fixture::new(Importance::WorldCupQuarterFinals);
+    /// fixture.knockout = true;
+    /// fixture.shootout_winner = Some(Side::PlayerTwo);
+    /// ```
+    pub fn new(importance: Importance) -> Fixture {
+        return Fixture {
+            importance,
+            knockout: false,
+            shootout_winner: None,
+        }
+    }
+}
+
+/// FifaRanking.
+pub struct FifaRanking<F: Float> {
+    ranking: EloRanking<F>,
+}
+
+impl<F: Float> FifaRanking<F> {
+    /// Create the ranking.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::fifa::FifaRanking;
+    /// let fifa = FifaRanking::<f64>::new();
+    /// ```
+    pub fn new() -> FifaRanking<F> {
+        // The importance scales the changes of a ranking with a K factor
+        // of 1.
+        let mut ranking = EloRanking::from_k_factor(1);
+        ranking.set_scale(F::from_f64(600.0));
+        return FifaRanking {
+            ranking,
+        }
+    }
+
+    /// Returns the underlying ranking, with a K factor of 1, for
+    /// predictions.
+    pub fn get_ranking(&self) -> &EloRanking<F> {
+        return &self.ranking;
+    }
+
+    /// Returns the point changes of both teams in a match.
+    ///
+    /// The outcome of the result is that after extra time. If a draw was
+    /// decided by a penalty shootout, the winner of the shootout scores 0.75
+    /// and the loser 0.5, so both can gain points. Any advantage or margin
+    /// of the result is ignored.
+    pub fn rating_change<T: Elo<F>>(&self,
+                                    team_one: &T,
+                                    team_two: &T,
+                                    result: &MatchResult<F>,
+                                    fixture: &Fixture) -> Result<(F, F), Error> {
+        let score = result.outcome.score()?;
+        let (score_one, score_two) = match (result.outcome, fixture.shootout_winner) {
+            (Outcome::Draw, Some(Side::PlayerOne)) => (0.75, 0.5),
+            (Outcome::Draw, Some(Side::PlayerTwo)) => (0.5, 0.75),
+            _ => (score.to_f64(), 1.0 - score.to_f64()),
+        };
+        let importance = F::from_f64(fixture.importance.coefficient());
+        let zero = F::from_f64(0.0);
+        let mut changes = [
+            self.ranking.rating_change(team_one, team_two, F::from_f64(score_one)).0,
+            self.ranking.rating_change(team_two, team_one, F::from_f64(score_two)).0,
+        ];
+        for change in changes.iter_mut() {
+            *change = *change * importance;
+            if fixture.knockout && *change < zero {
+                *change = zero;
+            }
+        }
+        return Ok((changes[0], changes[1]));
+    }
+
+    /// Apply the result of a match between two of the teams.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, MatchResult, Outcome};
+    /// # use elo::fifa::{FifaRanking, Fixture, Importance};
+    /// # struct Team { points: f64 }
+    /// # impl Elo<f64> for Team {
+    /// #     fn get_rating(&self) -> f64 { self.points }
+    /// #     fn change_rating(&mut self, points: f64) { self.points += points; }
+    /// # }
+    /// let fifa = FifaRanking::new();
+    /// let mut teams = vec![Team { points: 1500.0 }, Team { points: 1500.0 }];
+    /// let result = MatchResult::new(0, 1, Outcome::Win);
+    /// fifa.apply(&mut teams, &result, &Fixture::new(Importance::Friendly)).unwrap();
+    /// assert_eq!(1505.0, teams[0].points);
+    /// assert_eq!(1495.0, teams[1].points);
+    /// ```
+    pub fn apply<T: Elo<F>>(&self,
+                            teams: &mut [T],
+                            result: &MatchResult<F>,
+                            fixture: &Fixture) -> Result<(), Error> {
+        result.validate(teams.len())?;
+        let (change_one, change_two) = self.rating_change(
+            &teams[result.player_one],
+            &teams[result.player_two],
+            result,
+            fixture
+        )?;
+        teams[result.player_one].change_rating(change_one);
+        teams[result.player_two].change_rating(change_two);
+        return Ok(());
+    }
+}
+
+impl<F: Float> Default for FifaRanking<F> {
+    fn default() -> FifaRanking<F> {
+        return FifaRanking::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Team {
+        points: f64,
+    }
+
+    impl Elo<f64> for Team {
+        fn get_rating(&self) -> f64 {
+            return self.points;
+        }
+        fn change_rating(&mut self, points: f64) {
+            self.points += points;
+        }
+    }
+
+    /// The expected result of a team ahead by a number of points.
+    fn expected(difference: f64) -> f64 {
+        return 1.0 / (10f64.powf(-difference / 600.0) + 1.0);
+    }
+
+    fn change(fifa: &FifaRanking<f64>,
+              one: f64,
+              two: f64,
+              outcome: Outcome<f64>,
+              fixture: &Fixture) -> (f64, f64) {
+        return fifa.rating_change(&Team { points: one },
+                                  &Team { points: two },
+                                  &MatchResult::new(0, 1, outcome),
+                                  fixture).unwrap();
+    }
+
+    // The expected values follow from the published formula and
+    // coefficients; they are not taken from published ranking tables.
+
+    #[test]
+    fn sum_formula() {
+        let fifa = FifaRanking::new();
+        let fixture = Fixture::new(Importance::WorldCup);
+        // A win of the weaker team in a group match.
+        let (one, two) = change(&fifa, 1500.0, 1620.0, Outcome::Win, &fixture);
+        assert!((one - 50.0 * (1.0 - expected(-120.0))).abs() < 1e-9);
+        assert!((one + two).abs() < 1e-9);
+        // A draw between teams 100 points apart in a qualifier.
+        let (one, _) = change(&fifa, 1600.0, 1500.0, Outcome::Draw,
+                              &Fixture::new(Importance::Qualifier));
+        assert!((one - 25.0 * (0.5 - expected(100.0))).abs() < 1e-9);
+        assert!((one + 2.37).abs() < 0.005);
+        // There is no home advantage.
+        let mut result = MatchResult::new(0, 1, Outcome::Win);
+        result.advantage = Some(Side::PlayerOne);
+        let (one, _) = fifa.rating_change(&Team { points: 1500.0 },
+                                          &Team { points: 1500.0 },
+                                          &result,
+                                          &Fixture::new(Importance::Friendly))
+            .unwrap();
+        assert_eq!(5.0, one);
+    }
+
+    #[test]
+    fn knockout_stages() {
+        let fifa = FifaRanking::new();
+        let mut fixture = Fixture::new(Importance::WorldCupQuarterFinals);
+        fixture.knockout = true;
+        // The favorite loses but keeps its points.
+        let (one, two) = change(&fifa, 1800.0, 1700.0, Outcome::Loss, &fixture);
+        assert_eq!(0.0, one);
+        assert!((two - 60.0 * (1.0 - expected(-100.0))).abs() < 1e-9);
+        // Outside of the knockout stage it would have lost them.
+        fixture.knockout = false;
+        let (one, _) = change(&fifa, 1800.0, 1700.0, Outcome::Loss, &fixture);
+        assert!((one + 60.0 * expected(100.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn penalty_shootouts() {
+        let fifa = FifaRanking::new();
+        let mut fixture = Fixture::new(Importance::ConfederationQuarterFinals);
+        fixture.knockout = true;
+        fixture.shootout_winner = Some(Side::PlayerTwo);
+        // Equal teams: the winner gains a quarter of I, the loser nothing.
+        let (one, two) = change(&fifa, 1500.0, 1500.0, Outcome::Draw, &fixture);
+        assert_eq!(0.0, one);
+        assert_eq!(10.0, two);
+        // The weaker loser of a shootout gains points too.
+        fixture.knockout = false;
+        let (one, two) = change(&fifa, 1400.0, 1600.0, Outcome::Draw, &fixture);
+        assert!((one - 40.0 * (0.5 - expected(-200.0))).abs() < 1e-9);
+        assert!(one > 0.0);
+        assert!((two - 40.0 * (0.75 - expected(200.0))).abs() < 1e-9);
+        // A shootout winner only counts after a draw.
+        let (one, _) = change(&fifa, 1500.0, 1500.0, Outcome::Win, &fixture);
+        assert_eq!(20.0, one);
+    }
+
+    #[test]
+    fn points_tables() {
+        // Points before and after, to the two decimals of the ranking
+        // tables, worked out by hand from the published formula.
+        let fifa = FifaRanking::new();
+        let round = |points: f64| (points * 100.0).round() / 100.0;
+
+        // A World Cup round of 16 match won in regular time by the weaker
+        // team.
+        let mut teams = vec![Team { points: 1750.0 }, Team { points: 1670.0 }];
+        let mut fixture = Fixture::new(Importance::WorldCup);
+        fixture.knockout = true;
+        fifa.apply(&mut teams, &MatchResult::new(1, 0, Outcome::Win), &fixture)
+            .unwrap();
+        assert_eq!(1750.0, round(teams[0].points));
+        assert_eq!(1698.81, round(teams[1].points));
+
+        // A World Cup quarter-final won on penalties by the weaker team.
+        let mut teams = vec![Team { points: 1750.0 }, Team { points: 1650.0 }];
+        let mut fixture = Fixture::new(Importance::WorldCupQuarterFinals);
+        fixture.knockout = true;
+        fixture.shootout_winner = Some(Side::PlayerTwo);
+        fifa.apply(&mut teams, &MatchResult::new(0, 1, Outcome::Draw), &fixture)
+            .unwrap();
+        assert_eq!(1750.0, round(teams[0].points));
+        assert_eq!(1670.69, round(teams[1].points));
+
+        // A drawn friendly.
+        let mut teams = vec![Team { points: 1530.0 }, Team { points: 1500.0 }];
+        fifa.apply(&mut teams, &MatchResult::new(0, 1, Outcome::Draw),
+                   &Fixture::new(Importance::Friendly))
+            .unwrap();
+        assert_eq!(1529.71, round(teams[0].points));
+        assert_eq!(1500.29, round(teams[1].points));
+    }
+}
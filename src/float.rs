@@ -0,0 +1,81 @@
+//! Floating point types usable as ratings.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Float.
+///
+/// The arithmetic needed by `EloRanking`. It is implemented for `f32` and
+/// `f64`, and can be implemented for other numeric types, such as fixed
+/// point or higher precision numbers.
+pub trait Float: Copy + PartialOrd +
+    Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> +
+    Div<Output = Self> + Neg<Output = Self> {
+    /// Convert from an `f64`, rounding if needed.
+    fn from_f64(value: f64) -> Self;
+    /// Convert to an `f64`, rounding if needed.
+    fn to_f64(self) -> f64;
+    /// Raise to a power.
+    fn powf(self, exponent: Self) -> Self;
+    /// The exponential function.
+    fn exp(self) -> Self;
+    /// The natural logarithm.
+    fn ln(self) -> Self;
+    /// The square root.
+    fn sqrt(self) -> Self;
+    /// The absolute value.
+    fn abs(self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Float for $t {
+            fn from_f64(value: f64) -> $t {
+                return value as $t;
+            }
+            fn to_f64(self) -> f64 {
+                return self as f64;
+            }
+            fn powf(self, exponent: $t) -> $t {
+                return $t::powf(self, exponent);
+            }
+            fn exp(self) -> $t {
+                return $t::exp(self);
+            }
+            fn ln(self) -> $t {
+                return $t::ln(self);
+            }
+            fn sqrt(self) -> $t {
+                return $t::sqrt(self);
+            }
+            fn abs(self) -> $t {
+                return $t::abs(self);
+            }
+        }
+    }
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// IntoFloat.
+///
+/// Primitive numbers that convert to any `Float` type, so parameters such as
+/// the K factor can be given as integers or real numbers.
+pub trait IntoFloat {
+    /// Convert to a `Float` type, rounding if needed.
+    fn into_float<F: Float>(self) -> F;
+}
+
+macro_rules! impl_into_float {
+    ($($t:ident)*) => {
+        $(
+            impl IntoFloat for $t {
+                fn into_float<F: Float>(self) -> F {
+                    return F::from_f64(self as f64);
+                }
+            }
+        )*
+    }
+}
+
+impl_into_float!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
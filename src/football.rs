@@ -0,0 +1,253 @@
+//! The World Football Elo Ratings of eloratings.net.
+//!
+//! National teams are rated with the Elo curve and a K factor weighted by
+//! the importance of the match and increased for wide goal differences. The
+//! home team gets a bonus of 100 points, and a match decided by a penalty
+//! shootout counts as a draw.
+
+use {Elo, EloRanking, Error, Float, MarginOfVictory, MatchResult, Outcome, Side};
+
+/// The importance of a match, which sets its K factor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Importance {
+    /// World Cup finals: 60.
+    WorldCupFinals,
+    /// Continental championship finals and major intercontinental
+    /// tournaments: 50.
+    ContinentalFinals,
+    /// World Cup and continental qualifiers and major tournaments: 40.
+    Qualifier,
+    /// All other tournaments: 30.
+    Tournament,
+    /// Friendly matches: 20.
+    Friendly,
+}
+
+impl Importance {
+    /// Returns the K factor of the importance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::football::Importance;
+    /// assert_eq!(60.0, Importance::WorldCupFinals.k_factor());
+    /// ```
+    pub fn k_factor(&self) -> f64 {
+        return match *self {
+            Importance::WorldCupFinals => 60.0,
+            Importance::ContinentalFinals => 50.0,
+            Importance::Qualifier => 40.0,
+            Importance::Tournament => 30.0,
+            Importance::Friendly => 20.0,
+        };
+    }
+}
+
+/// GoalDifference.
+///
+/// The goal difference index: the K factor is increased by half for a win
+/// by two goals, by three quarters for three goals, and by a further eighth
+/// for every goal beyond.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GoalDifference;
+
+impl<F: Float> MarginOfVictory<F> for GoalDifference {
+    fn multiplier(&self, margin: F, _winner_difference: F) -> F {
+        let goals = margin.abs().to_f64().round();
+        let multiplier = if goals <= 1.0 {
+            1.0
+        } else if goals == 2.0 {
+            1.5
+        } else {
+            1.75 + (goals - 3.0) / 8.0
+        };
+        return F::from_f64(multiplier);
+    }
+}
+
+/// WorldFootball.
+pub struct WorldFootball<F: Float> {
+    ranking: EloRanking<F>,
+}
+
+impl<F: Float> WorldFootball<F> {
+    /// Create the ruleset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::football::WorldFootball;
+    /// let world_football = WorldFootball::<f64>::new();
+    /// ```
+    pub fn new() -> WorldFootball<F> {
+        // The K factor of a match is that of its importance, which scales
+        // the changes of a ranking with a K factor of 1.
+        let mut ranking = EloRanking::from_k_factor(1);
+        ranking.set_advantage(F::from_f64(100.0));
+        ranking.set_margin_of_victory(GoalDifference);
+        return WorldFootball {
+            ranking,
+        }
+    }
+
+    /// Returns the underlying ranking, with a K factor of 1, for
+    /// predictions.
+    pub fn get_ranking(&self) -> &EloRanking<F> {
+        return &self.ranking;
+    }
+
+    /// Create the result of a match from its score.
+    ///
+    /// The first team plays at home unless the match is on neutral ground,
+    /// and `shootout` tells whether the match was decided by a penalty
+    /// shootout, which counts as a draw.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{MatchResult, Outcome, Side};
+    /// # use elo::football::WorldFootball;
+    /// let result = WorldFootball::<f64>::result(0, 1, 3, 1, false, false);
+    /// assert_eq!(Outcome::Win, result.outcome);
+    /// assert_eq!(Some(2.0), result.margin);
+    /// assert_eq!(Some(Side::PlayerOne), result.advantage);
+    /// ```
+    pub fn result(home: usize,
+                  away: usize,
+                  home_goals: u32,
+                  away_goals: u32,
+                  neutral: bool,
+                  shootout: bool) -> MatchResult<F> {
+        let outcome = if shootout || home_goals == away_goals {
+            Outcome::Draw
+        } else if home_goals > away_goals {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        };
+        let mut result = MatchResult::new(home, away, outcome);
+        result.margin = Some(F::from_f64(
+            (home_goals as f64 - away_goals as f64).abs()
+        ));
+        if !neutral {
+            result.advantage = Some(Side::PlayerOne);
+        }
+        return result;
+    }
+
+    /// Returns the rating changes of both teams for a result.
+    pub fn rating_change<T: Elo<F>>(&self,
+                                    team_one: &T,
+                                    team_two: &T,
+                                    result: &MatchResult<F>,
+                                    importance: Importance) -> Result<(F, F), Error> {
+        let (change_one, change_two) = self.ranking.rating_change_with_margin(
+            team_one,
+            team_two,
+            result.outcome.score()?,
+            result.margin.unwrap_or_else(|| F::from_f64(0.0)),
+            result.advantage
+        );
+        let k_factor = F::from_f64(importance.k_factor());
+        return Ok((change_one * k_factor, change_two * k_factor));
+    }
+
+    /// Apply the result of a match between two of the teams.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Elo;
+    /// # use elo::football::{Importance, WorldFootball};
+    /// # struct Team { rating: f64 }
+    /// # impl Elo<f64> for Team {
+    /// #     fn get_rating(&self) -> f64 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f64) { self.rating += rating; }
+    /// # }
+    /// let world_football = WorldFootball::new();
+    /// let mut teams = vec![Team { rating: 1900.0 }, Team { rating: 2000.0 }];
+    /// let result = WorldFootball::result(0, 1, 2, 0, false, false);
+    /// world_football.apply(&mut teams, &result, Importance::Friendly).unwrap();
+    /// // The home advantage made the match even, so the K factor of 20
+    /// // increased by half for two goals gives half of 30 points.
+    /// assert!((teams[0].rating - 1915.0).abs() < 1e-9);
+    /// ```
+    pub fn apply<T: Elo<F>>(&self,
+                            teams: &mut [T],
+                            result: &MatchResult<F>,
+                            importance: Importance) -> Result<(), Error> {
+        result.validate(teams.len())?;
+        let (change_one, change_two) = self.rating_change(
+            &teams[result.player_one],
+            &teams[result.player_two],
+            result,
+            importance
+        )?;
+        teams[result.player_one].change_rating(change_one);
+        teams[result.player_two].change_rating(change_two);
+        return Ok(());
+    }
+}
+
+impl<F: Float> Default for WorldFootball<F> {
+    fn default() -> WorldFootball<F> {
+        return WorldFootball::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Team {
+        rating: f64,
+    }
+
+    impl Elo<f64> for Team {
+        fn get_rating(&self) -> f64 {
+            return self.rating;
+        }
+        fn change_rating(&mut self, rating: f64) {
+            self.rating += rating;
+        }
+    }
+
+    #[test]
+    fn goal_difference() {
+        let index = |goals: f64| GoalDifference.multiplier(goals, 0.0);
+        assert_eq!(1.0, index(0.0));
+        assert_eq!(1.0, index(1.0));
+        assert_eq!(1.5, index(2.0));
+        assert_eq!(1.75, index(3.0));
+        assert_eq!(1.875, index(4.0));
+        assert_eq!(2.25, index(7.0));
+    }
+
+    #[test]
+    fn matches() {
+        let world_football = WorldFootball::new();
+        let mut teams = vec![Team { rating: 2000.0 }, Team { rating: 1800.0 }];
+        // A World Cup finals win by three goals on neutral ground.
+        let result = WorldFootball::result(0, 1, 3, 0, true, false);
+        let expected = 1.0 / (1.0 + 10f64.powf(-200.0 / 400.0));
+        world_football.apply(&mut teams, &result, Importance::WorldCupFinals)
+            .unwrap();
+        assert!((teams[0].rating - 2000.0 - 60.0 * 1.75 * (1.0 - expected)).abs()
+                < 1e-9);
+        assert!((teams[0].rating + teams[1].rating - 3800.0).abs() < 1e-9);
+
+        // A qualifier lost at home on penalties is a draw.
+        let mut teams = vec![Team { rating: 1700.0 }, Team { rating: 1700.0 }];
+        let result = WorldFootball::result(0, 1, 1, 1, false, true);
+        world_football.apply(&mut teams, &result, Importance::Qualifier).unwrap();
+        let expected = 1.0 / (1.0 + 10f64.powf(-100.0 / 400.0));
+        assert!((teams[0].rating - 1700.0 - 40.0 * (0.5 - expected)).abs() < 1e-9);
+
+        // An away win by one goal.
+        let result = WorldFootball::result(1, 0, 0, 1, false, false);
+        assert_eq!(Outcome::Loss, result.outcome);
+        let (change, _) = world_football.rating_change(
+            &teams[1], &teams[0], &result, Importance::Tournament).unwrap();
+        assert!(change < 0.0);
+    }
+}
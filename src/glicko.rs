@@ -0,0 +1,290 @@
+//! The original Glicko rating system.
+//!
+//! Glicko adds a rating deviation (RD) to the Elo model. The RD shrinks as a
+//! player competes and grows again, by the system constant c, for every
+//! rating period the player is inactive.
+
+use std::f64::consts::{LN_10, PI};
+
+/// The rating deviation of an unrated player, and the highest RD possible.
+const MAX_DEVIATION: f64 = 350.0;
+
+/// Glicko.
+pub trait Glicko {
+    /// Get the rating.
+    fn get_rating(&self) -> f64;
+    /// Get the rating deviation.
+    fn get_deviation(&self) -> f64;
+    /// Set the rating and rating deviation.
+    fn set_rating(&mut self, rating: f64, deviation: f64);
+}
+
+const Q: f64 = LN_10 / 400.0;
+
+fn g(deviation: f64) -> f64 {
+    return 1.0 / (1.0 + 3.0 * Q * Q * deviation * deviation / (PI * PI)).sqrt();
+}
+
+fn expected(rating: f64, opponent_rating: f64, opponent_deviation: f64) -> f64 {
+    return 1.0 / (1.0 + 10f64.powf(
+        -g(opponent_deviation) * (rating - opponent_rating) / 400.0
+    ));
+}
+
+/// GlickoRanking.
+pub struct GlickoRanking {
+    c: f64,
+}
+
+impl GlickoRanking {
+    /// Create a new Glicko ranking system.
+    ///
+    /// The constant c controls how much the rating deviation grows for each
+    /// rating period.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko::GlickoRanking;
+    /// let c: f64 = 34.6;
+    /// let glicko_ranking = GlickoRanking::new(c);
+    /// ```
+    pub fn new(c: f64) -> GlickoRanking {
+        return GlickoRanking {
+            c,
+        }
+    }
+
+    /// Change the constant c.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko::GlickoRanking;
+    /// # let mut glicko_ranking = GlickoRanking::new(34.6);
+    /// glicko_ranking.set_c(63.2);
+    /// ```
+    pub fn set_c(&mut self, c: f64) {
+        self.c = c;
+    }
+
+    /// Returns the constant c.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko::GlickoRanking;
+    /// # let glicko_ranking = GlickoRanking::new(34.6);
+    /// assert_eq!(34.6, glicko_ranking.get_c());
+    /// ```
+    pub fn get_c(&self) -> f64 {
+        return self.c;
+    }
+
+    /// Internal method for the rating deviation after inactive periods.
+    fn decayed_deviation(&self, deviation: f64, periods: usize) -> f64 {
+        let grown = (deviation * deviation +
+                     self.c * self.c * periods as f64).sqrt();
+        return grown.min(MAX_DEVIATION);
+    }
+
+    /// Increase the rating deviation of a player for a number of rating
+    /// periods without games.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko::{Glicko, GlickoRanking};
+    /// # struct Player { rating: f64, deviation: f64 }
+    /// # impl Glicko for Player {
+    /// #     fn get_rating(&self) -> f64 { self.rating }
+    /// #     fn get_deviation(&self) -> f64 { self.deviation }
+    /// #     fn set_rating(&mut self, r: f64, d: f64) {
+    /// #         self.rating = r; self.deviation = d;
+    /// #     }
+    /// # }
+    /// let mut player = Player { rating: 1700.0, deviation: 50.0 };
+    /// let glicko_ranking = GlickoRanking::new(34.6);
+    /// glicko_ranking.decay(&mut player, 200);
+    /// assert_eq!(350.0, player.get_deviation());
+    /// ```
+    pub fn decay<T: Glicko>(&self, player: &mut T, periods: usize) {
+        let deviation = self.decayed_deviation(player.get_deviation(), periods);
+        let rating = player.get_rating();
+        player.set_rating(rating, deviation);
+    }
+
+    /// Internal method for rating a single player over a rating period.
+    ///
+    /// Each game is given as the opponent's rating, rating deviation and the
+    /// score of the player.
+    fn rate(&self,
+            player: (f64, f64),
+            games: &[(f64, f64, f64)]) -> (f64, f64) {
+        let (rating, deviation) = player;
+        if games.is_empty() {
+            return (rating, deviation);
+        }
+        let mut d_inverse = 0.0;
+        let mut improvement = 0.0;
+        for &(opponent_rating, opponent_deviation, score) in games {
+            let e = expected(rating, opponent_rating, opponent_deviation);
+            let g_j = g(opponent_deviation);
+            d_inverse += Q * Q * g_j * g_j * e * (1.0 - e);
+            improvement += g_j * (score - e);
+        }
+        let precision = 1.0 / (deviation * deviation) + d_inverse;
+        return (rating + Q / precision * improvement, (1.0 / precision).sqrt());
+    }
+
+    /// Update every player from the games of one rating period.
+    ///
+    /// Each game is given as the indices of the two players and the score of
+    /// the first one. The rating deviation of every player first grows by
+    /// one period, then all games are evaluated against the ratings at the
+    /// start of the period.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko::{Glicko, GlickoRanking};
+    /// # struct Player { rating: f64, deviation: f64 }
+    /// # impl Glicko for Player {
+    /// #     fn get_rating(&self) -> f64 { self.rating }
+    /// #     fn get_deviation(&self) -> f64 { self.deviation }
+    /// #     fn set_rating(&mut self, r: f64, d: f64) {
+    /// #         self.rating = r; self.deviation = d;
+    /// #     }
+    /// # }
+    /// # let new_player = || Player { rating: 1500.0, deviation: 350.0 };
+    /// let mut players = vec![new_player(), new_player(), new_player()];
+    /// let glicko_ranking = GlickoRanking::new(34.6);
+    /// glicko_ranking.rating_period(&mut players, &[(0, 1, 1.0), (0, 2, 0.5)]);
+    /// assert!(players[0].get_rating() > 1500.0);
+    /// ```
+    pub fn rating_period<T: Glicko>(&self,
+                                    players: &mut [T],
+                                    games: &[(usize, usize, f64)]) {
+        let start: Vec<(f64, f64)> = players.iter()
+            .map(|player| {
+                (player.get_rating(),
+                 self.decayed_deviation(player.get_deviation(), 1))
+            })
+            .collect();
+        let mut results: Vec<Vec<(f64, f64, f64)>> =
+            players.iter().map(|_| Vec::new()).collect();
+        for &(one, two, score) in games {
+            results[one].push((start[two].0, start[two].1, score));
+            results[two].push((start[one].0, start[one].1, 1.0 - score));
+        }
+        for ((player, &rating), games) in
+            players.iter_mut().zip(start.iter()).zip(results.iter()) {
+            let (rating, deviation) = self.rate(rating, games);
+            player.set_rating(rating, deviation);
+        }
+    }
+
+    /// Internal method for a rating period containing a single game.
+    fn calculate_rating<T: Glicko>(&self,
+                                   player_one: &mut T,
+                                   player_two: &mut T,
+                                   score: f64) {
+        let one = (player_one.get_rating(),
+                   self.decayed_deviation(player_one.get_deviation(), 1));
+        let two = (player_two.get_rating(),
+                   self.decayed_deviation(player_two.get_deviation(), 1));
+        let (r, d) = self.rate(one, &[(two.0, two.1, score)]);
+        player_one.set_rating(r, d);
+        let (r, d) = self.rate(two, &[(one.0, one.1, 1.0 - score)]);
+        player_two.set_rating(r, d);
+    }
+
+    pub fn win<T: Glicko>(&self, winner: &mut T, loser: &mut T) {
+        self.calculate_rating(winner, loser, 1.0);
+    }
+
+    pub fn tie<T: Glicko>(&self, player_one: &mut T, player_two: &mut T) {
+        self.calculate_rating(player_one, player_two, 0.5);
+    }
+
+    pub fn loss<T: Glicko>(&self, loser: &mut T, winner: &mut T) {
+        self.win::<T>(winner, loser);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RatingObject {
+        rating: f64,
+        deviation: f64,
+    }
+
+    impl RatingObject {
+        pub fn new(rating: f64, deviation: f64) -> RatingObject {
+            return RatingObject {
+                rating,
+                deviation,
+            };
+        }
+    }
+
+    impl Glicko for RatingObject {
+        fn get_rating(&self) -> f64 {
+            return self.rating;
+        }
+        fn get_deviation(&self) -> f64 {
+            return self.deviation;
+        }
+        fn set_rating(&mut self, rating: f64, deviation: f64) {
+            self.rating = rating;
+            self.deviation = deviation;
+        }
+    }
+
+    #[test]
+    fn glickman_example() {
+        // The worked example from Glickman's "The Glicko system", with the
+        // start-of-period deviations already given.
+        let mut players = vec![
+            RatingObject::new(1500.0, 200.0),
+            RatingObject::new(1400.0, 30.0),
+            RatingObject::new(1550.0, 100.0),
+            RatingObject::new(1700.0, 300.0),
+        ];
+        let rating_system = GlickoRanking::new(0.0);
+        rating_system.rating_period(&mut players,
+                                    &[(0, 1, 1.0), (0, 2, 0.0), (0, 3, 0.0)]);
+        assert!((players[0].get_rating() - 1464.1).abs() < 0.1);
+        assert!((players[0].get_deviation() - 151.4).abs() < 0.1);
+    }
+
+    #[test]
+    fn decay() {
+        let mut player = RatingObject::new(1500.0, 50.0);
+        let rating_system = GlickoRanking::new(30.0);
+        rating_system.decay(&mut player, 1);
+        assert!((player.get_deviation() - (50f64 * 50.0 + 900.0).sqrt()).abs()
+                < 1e-9);
+        // The deviation never exceeds that of an unrated player.
+        rating_system.decay(&mut player, 1000);
+        assert_eq!(350.0, player.get_deviation());
+        assert_eq!(1500.0, player.get_rating());
+    }
+
+    #[test]
+    fn inactive_players() {
+        let mut players = vec![
+            RatingObject::new(1500.0, 50.0),
+            RatingObject::new(1500.0, 50.0),
+            RatingObject::new(1500.0, 50.0),
+        ];
+        let rating_system = GlickoRanking::new(30.0);
+        rating_system.rating_period(&mut players, &[(0, 1, 1.0)]);
+        assert!(players[0].get_rating() > 1500.0);
+        assert!(players[1].get_rating() < 1500.0);
+        assert_eq!(1500.0, players[2].get_rating());
+        assert!(players[2].get_deviation() > 50.0);
+    }
+}
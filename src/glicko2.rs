@@ -0,0 +1,323 @@
+//! The Glicko-2 rating system.
+//!
+//! Glicko-2 extends the Elo model with a rating deviation (RD), which
+//! measures how reliable a rating is, and a volatility, which measures how
+//! erratic a player's performances are. Ratings are updated in rating
+//! periods: every game in a period is evaluated against the ratings the
+//! players had at the start of it.
+
+use std::f64::consts::PI;
+
+/// Conversion factor between the Glicko and Glicko-2 scales.
+const SCALE: f64 = 173.7178;
+
+/// Convergence tolerance of the volatility iteration.
+const EPSILON: f64 = 0.000001;
+
+/// Glicko-2.
+pub trait Glicko2 {
+    /// Get the rating.
+    fn get_rating(&self) -> f64;
+    /// Get the rating deviation.
+    fn get_deviation(&self) -> f64;
+    /// Get the volatility.
+    fn get_volatility(&self) -> f64;
+    /// Set the rating, rating deviation and volatility.
+    fn set_rating(&mut self, rating: f64, deviation: f64, volatility: f64);
+}
+
+fn g(phi: f64) -> f64 {
+    return 1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt();
+}
+
+fn expected(mu: f64, mu_j: f64, phi_j: f64) -> f64 {
+    return 1.0 / (1.0 + (-g(phi_j) * (mu - mu_j)).exp());
+}
+
+/// Glicko2Ranking.
+pub struct Glicko2Ranking {
+    tau: f64,
+}
+
+impl Glicko2Ranking {
+    /// Create a new Glicko-2 ranking system.
+    ///
+    /// Tau constrains the change in volatility over time. Glickman suggests
+    /// values between 0.3 and 1.2.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko2::Glicko2Ranking;
+    /// let tau: f64 = 0.5;
+    /// let glicko2_ranking = Glicko2Ranking::new(tau);
+    /// ```
+    pub fn new(tau: f64) -> Glicko2Ranking {
+        return Glicko2Ranking {
+            tau,
+        }
+    }
+
+    /// Change the system constant tau.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko2::Glicko2Ranking;
+    /// # let mut glicko2_ranking = Glicko2Ranking::new(0.5);
+    /// glicko2_ranking.set_tau(0.3);
+    /// ```
+    pub fn set_tau(&mut self, tau: f64) {
+        self.tau = tau;
+    }
+
+    /// Returns the system constant tau.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko2::Glicko2Ranking;
+    /// # let glicko2_ranking = Glicko2Ranking::new(0.5);
+    /// assert_eq!(0.5, glicko2_ranking.get_tau());
+    /// ```
+    pub fn get_tau(&self) -> f64 {
+        return self.tau;
+    }
+
+    /// Internal method for the new volatility (step 5 of Glickman's paper).
+    fn volatility(&self, phi: f64, sigma: f64, v: f64, delta: f64) -> f64 {
+        let a = (sigma * sigma).ln();
+        let tau = self.tau;
+        let f = |x: f64| {
+            let ex = x.exp();
+            let denominator = phi * phi + v + ex;
+            ex * (delta * delta - phi * phi - v - ex) /
+                (2.0 * denominator * denominator) - (x - a) / (tau * tau)
+        };
+        let mut big_a = a;
+        let mut big_b = if delta * delta > phi * phi + v {
+            (delta * delta - phi * phi - v).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * tau) < 0.0 {
+                k += 1.0;
+            }
+            a - k * tau
+        };
+        let mut f_a = f(big_a);
+        let mut f_b = f(big_b);
+        while (big_b - big_a).abs() > EPSILON {
+            let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+            let f_c = f(big_c);
+            if f_c * f_b <= 0.0 {
+                big_a = big_b;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+            big_b = big_c;
+            f_b = f_c;
+        }
+        return (big_a / 2.0).exp();
+    }
+
+    /// Internal method for rating a single player over a rating period.
+    ///
+    /// Each game is given as the opponent's rating, rating deviation and the
+    /// score of the player.
+    fn rate(&self,
+            player: (f64, f64, f64),
+            games: &[(f64, f64, f64)]) -> (f64, f64, f64) {
+        let (rating, deviation, sigma) = player;
+        let mu = (rating - 1500.0) / SCALE;
+        let phi = deviation / SCALE;
+        if games.is_empty() {
+            let phi_star = (phi * phi + sigma * sigma).sqrt();
+            return (rating, phi_star * SCALE, sigma);
+        }
+        let mut v_inverse = 0.0;
+        let mut improvement = 0.0;
+        for &(opponent_rating, opponent_deviation, score) in games {
+            let mu_j = (opponent_rating - 1500.0) / SCALE;
+            let phi_j = opponent_deviation / SCALE;
+            let e = expected(mu, mu_j, phi_j);
+            v_inverse += g(phi_j) * g(phi_j) * e * (1.0 - e);
+            improvement += g(phi_j) * (score - e);
+        }
+        let v = 1.0 / v_inverse;
+        let sigma_prime = self.volatility(phi, sigma, v, v * improvement);
+        let phi_star = (phi * phi + sigma_prime * sigma_prime).sqrt();
+        let phi_prime = 1.0 / (1.0 / (phi_star * phi_star) + v_inverse).sqrt();
+        let mu_prime = mu + phi_prime * phi_prime * improvement;
+        return (mu_prime * SCALE + 1500.0, phi_prime * SCALE, sigma_prime);
+    }
+
+    /// Update every player from the games of one rating period.
+    ///
+    /// Each game is given as the indices of the two players and the score of
+    /// the first one. All games are evaluated against the ratings at the
+    /// start of the period. Players without any games have their rating
+    /// deviation increased.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::glicko2::{Glicko2, Glicko2Ranking};
+    /// # struct Player { rating: f64, deviation: f64, volatility: f64 }
+    /// # impl Glicko2 for Player {
+    /// #     fn get_rating(&self) -> f64 { self.rating }
+    /// #     fn get_deviation(&self) -> f64 { self.deviation }
+    /// #     fn get_volatility(&self) -> f64 { self.volatility }
+    /// #     fn set_rating(&mut self, r: f64, d: f64, v: f64) {
+    /// #         self.rating = r; self.deviation = d; self.volatility = v;
+    /// #     }
+    /// # }
+    /// # let new_player = || Player { rating: 1500.0, deviation: 350.0, volatility: 0.06 };
+    /// let mut players = vec![new_player(), new_player(), new_player()];
+    /// let glicko2_ranking = Glicko2Ranking::new(0.5);
+    /// glicko2_ranking.rating_period(&mut players, &[(0, 1, 1.0), (0, 2, 0.5)]);
+    /// assert!(players[0].get_rating() > 1500.0);
+    /// ```
+    pub fn rating_period<T: Glicko2>(&self,
+                                     players: &mut [T],
+                                     games: &[(usize, usize, f64)]) {
+        let mut results: Vec<Vec<(f64, f64, f64)>> =
+            players.iter().map(|_| Vec::new()).collect();
+        for &(one, two, score) in games {
+            results[one].push((players[two].get_rating(),
+                               players[two].get_deviation(),
+                               score));
+            results[two].push((players[one].get_rating(),
+                               players[one].get_deviation(),
+                               1.0 - score));
+        }
+        let ratings: Vec<(f64, f64, f64)> = players.iter()
+            .zip(results.iter())
+            .map(|(player, games)| {
+                self.rate((player.get_rating(),
+                           player.get_deviation(),
+                           player.get_volatility()),
+                          games)
+            })
+            .collect();
+        for (player, (rating, deviation, volatility)) in
+            players.iter_mut().zip(ratings) {
+            player.set_rating(rating, deviation, volatility);
+        }
+    }
+
+    /// Internal method for a rating period containing a single game.
+    fn calculate_rating<T: Glicko2>(&self,
+                                    player_one: &mut T,
+                                    player_two: &mut T,
+                                    score: f64) {
+        let one = (player_one.get_rating(),
+                   player_one.get_deviation(),
+                   player_one.get_volatility());
+        let two = (player_two.get_rating(),
+                   player_two.get_deviation(),
+                   player_two.get_volatility());
+        let (r, d, v) = self.rate(one, &[(two.0, two.1, score)]);
+        player_one.set_rating(r, d, v);
+        let (r, d, v) = self.rate(two, &[(one.0, one.1, 1.0 - score)]);
+        player_two.set_rating(r, d, v);
+    }
+
+    pub fn win<T: Glicko2>(&self, winner: &mut T, loser: &mut T) {
+        self.calculate_rating(winner, loser, 1.0);
+    }
+
+    pub fn tie<T: Glicko2>(&self, player_one: &mut T, player_two: &mut T) {
+        self.calculate_rating(player_one, player_two, 0.5);
+    }
+
+    pub fn loss<T: Glicko2>(&self, loser: &mut T, winner: &mut T) {
+        self.win::<T>(winner, loser);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RatingObject {
+        rating: f64,
+        deviation: f64,
+        volatility: f64,
+    }
+
+    impl RatingObject {
+        pub fn new(rating: f64, deviation: f64) -> RatingObject {
+            return RatingObject {
+                rating,
+                deviation,
+                volatility: 0.06,
+            };
+        }
+    }
+
+    impl Glicko2 for RatingObject {
+        fn get_rating(&self) -> f64 {
+            return self.rating;
+        }
+        fn get_deviation(&self) -> f64 {
+            return self.deviation;
+        }
+        fn get_volatility(&self) -> f64 {
+            return self.volatility;
+        }
+        fn set_rating(&mut self, rating: f64, deviation: f64, volatility: f64) {
+            self.rating = rating;
+            self.deviation = deviation;
+            self.volatility = volatility;
+        }
+    }
+
+    #[test]
+    fn glickman_example() {
+        // The worked example from Glickman's "Example of the Glicko-2 system".
+        let mut players = vec![
+            RatingObject::new(1500.0, 200.0),
+            RatingObject::new(1400.0, 30.0),
+            RatingObject::new(1550.0, 100.0),
+            RatingObject::new(1700.0, 300.0),
+        ];
+        let rating_system = Glicko2Ranking::new(0.5);
+        rating_system.rating_period(&mut players,
+                                    &[(0, 1, 1.0), (0, 2, 0.0), (0, 3, 0.0)]);
+        assert!((players[0].get_rating() - 1464.06).abs() < 0.01);
+        assert!((players[0].get_deviation() - 151.52).abs() < 0.01);
+        assert!((players[0].get_volatility() - 0.05999).abs() < 0.00001);
+    }
+
+    #[test]
+    fn single_games() {
+        let mut player_one = RatingObject::new(1500.0, 350.0);
+        let mut player_two = RatingObject::new(1500.0, 350.0);
+        let rating_system = Glicko2Ranking::new(0.5);
+        // In a tie between equal players, the ratings should stay the same.
+        rating_system.tie::<RatingObject>(&mut player_one, &mut player_two);
+        assert!((player_one.get_rating() - 1500.0).abs() < 1e-9);
+        assert!(player_one.get_deviation() < 350.0);
+        // With a win, player_one should gain exactly what player_two loses.
+        rating_system.win::<RatingObject>(&mut player_one, &mut player_two);
+        assert!(player_one.get_rating() > 1500.0);
+        assert!((player_one.get_rating() + player_two.get_rating() - 3000.0)
+                .abs() < 1e-9);
+    }
+
+    #[test]
+    fn inactive_players() {
+        let mut players = vec![
+            RatingObject::new(1500.0, 200.0),
+            RatingObject::new(1500.0, 200.0),
+        ];
+        let rating_system = Glicko2Ranking::new(0.5);
+        rating_system.rating_period(&mut players, &[]);
+        // The rating deviation grows by the volatility.
+        let phi = 200.0 / SCALE;
+        let expected = (phi * phi + 0.06 * 0.06).sqrt() * SCALE;
+        assert!((players[0].get_deviation() - expected).abs() < 1e-9);
+        assert_eq!(1500.0, players[0].get_rating());
+    }
+}
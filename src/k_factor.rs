@@ -0,0 +1,247 @@
+//! Policies choosing the K-factor of a player.
+
+use float::Float;
+use Elo;
+
+/// What a `KFactorPolicy` knows about a player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayerHistory<F> {
+    /// The current rating.
+    pub rating: F,
+    /// The number of rated games played so far.
+    pub games_played: usize,
+    /// The highest rating ever reached.
+    pub peak_rating: F,
+}
+
+/// KFactorPolicy.
+///
+/// Chooses the K-factor of a player from their history.
+pub trait KFactorPolicy<F: Float> {
+    /// Returns the K-factor of a player.
+    fn k_factor(&self, player: &PlayerHistory<F>) -> F;
+}
+
+/// How the K factors of two players combine in a game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UpdateMode {
+    /// Both ratings change by the same amount, using the mean K factor of
+    /// both players, so the sum of all ratings is conserved.
+    Symmetric,
+    /// Every rating changes by its own K factor, so a new player with a high
+    /// K factor can move a lot against a veteran who barely moves.
+    ///
+    /// The game is not zero-sum: whenever the K factors differ, the sum of
+    /// all ratings drifts, upwards when the player with the higher K factor
+    /// does better than expected and downwards otherwise. `rebalance` can
+    /// correct the drift of a rating pool.
+    Individual,
+}
+
+/// Shift every rating by the same amount so that the ratings sum to
+/// `total` again, correcting the drift of `UpdateMode::Individual`.
+///
+/// Ratings are shifted with `Elo::shift_rating`, so players whose
+/// `change_rating` counts games should override it.
+///
+/// # Example
+///
+/// ```
+/// # use elo::{rebalance, Elo};
+/// # struct Player { rating: f32 }
+/// # impl Elo for Player {
+/// #     fn get_rating(&self) -> f32 { self.rating }
+/// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+/// # }
+/// let mut players = vec![Player { rating: 1530.0 }, Player { rating: 1490.0 }];
+/// rebalance(&mut players, 3000.0);
+/// assert_eq!(1520.0, players[0].rating);
+/// assert_eq!(1480.0, players[1].rating);
+/// ```
+pub fn rebalance<F: Float, T: Elo<F>>(players: &mut [T], total: F) {
+    if players.is_empty() {
+        return;
+    }
+    let mut sum = F::from_f64(0.0);
+    for player in players.iter() {
+        sum = sum + player.get_rating();
+    }
+    let shift = (total - sum) / F::from_f64(players.len() as f64);
+    for player in players.iter_mut() {
+        player.shift_rating(shift);
+    }
+}
+
+/// Fide.
+///
+/// The K-factors of the FIDE rating regulations: 40 for the first 30 games,
+/// then 20 while the rating is below 2400, and 10 for good once a player has
+/// reached 2400.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fide;
+
+impl<F: Float> KFactorPolicy<F> for Fide {
+    fn k_factor(&self, player: &PlayerHistory<F>) -> F {
+        if player.peak_rating >= F::from_f64(2400.0) {
+            return F::from_f64(10.0);
+        }
+        if player.games_played < 30 {
+            return F::from_f64(40.0);
+        }
+        return F::from_f64(20.0);
+    }
+}
+
+/// Uscf.
+///
+/// The K-factor of the USCF rating system, 800 / (Ne + m), where m is the
+/// number of games in the event and Ne the effective number of previous
+/// games: the games played, capped by a number that grows with the rating.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Uscf {
+    event_games: usize,
+}
+
+impl Uscf {
+    /// Create the USCF policy for events of a number of games.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{KFactorPolicy, PlayerHistory, Uscf};
+    /// let uscf = Uscf::new(1);
+    /// let player = PlayerHistory { rating: 1500.0, games_played: 10, peak_rating: 1500.0 };
+    /// assert!((uscf.k_factor(&player) - 800.0 / 11.0f64).abs() < 1e-9);
+    /// ```
+    pub fn new(event_games: usize) -> Uscf {
+        return Uscf {
+            event_games,
+        }
+    }
+
+    /// Change the number of games in an event.
+    pub fn set_event_games(&mut self, event_games: usize) {
+        self.event_games = event_games;
+    }
+
+    /// Returns the number of games in an event.
+    pub fn get_event_games(&self) -> usize {
+        return self.event_games;
+    }
+}
+
+impl Default for Uscf {
+    /// Events of a single game.
+    fn default() -> Uscf {
+        return Uscf::new(1);
+    }
+}
+
+impl<F: Float> KFactorPolicy<F> for Uscf {
+    fn k_factor(&self, player: &PlayerHistory<F>) -> F {
+        let rating = player.rating.to_f64().min(2355.0);
+        let distance = 2569.0 - rating;
+        let effective = (50.0 / (0.662 + 0.00000739 * distance * distance).sqrt())
+            .min(player.games_played as f64);
+        return F::from_f64(800.0 / (effective + self.event_games as f64));
+    }
+}
+
+/// RatingBands.
+///
+/// A schedule of K-factors by rating band.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RatingBands<F> {
+    bands: Vec<(F, F)>,
+}
+
+impl<F: Float> RatingBands<F> {
+    /// Create a schedule from the lowest rating of every band and its
+    /// K-factor. Ratings below every band use the K-factor of the lowest
+    /// band.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no bands.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{KFactorPolicy, PlayerHistory, RatingBands};
+    /// let bands = RatingBands::new(vec![(0.0, 32.0), (2100.0, 24.0), (2400.0, 16.0)]);
+    /// let player = PlayerHistory { rating: 2200.0, games_played: 50, peak_rating: 2450.0 };
+    /// assert_eq!(24.0, bands.k_factor(&player));
+    /// ```
+    pub fn new(mut bands: Vec<(F, F)>) -> RatingBands<F> {
+        assert!(!bands.is_empty(), "RatingBands needs at least one band");
+        bands.sort_by(|a, b| a.0.partial_cmp(&b.0)
+                      .unwrap_or(::std::cmp::Ordering::Equal));
+        return RatingBands {
+            bands,
+        }
+    }
+
+    /// Returns the lowest rating and K-factor of every band.
+    pub fn get_bands(&self) -> &[(F, F)] {
+        return &self.bands;
+    }
+}
+
+impl<F: Float> KFactorPolicy<F> for RatingBands<F> {
+    fn k_factor(&self, player: &PlayerHistory<F>) -> F {
+        let band = self.bands.iter()
+            .rev()
+            .find(|band| band.0 <= player.rating)
+            .unwrap_or(&self.bands[0]);
+        return band.1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(rating: f64, games_played: usize, peak_rating: f64) -> PlayerHistory<f64> {
+        return PlayerHistory {
+            rating,
+            games_played,
+            peak_rating,
+        };
+    }
+
+    #[test]
+    fn fide() {
+        assert_eq!(40.0, Fide.k_factor(&history(1500.0, 0, 1500.0)));
+        assert_eq!(40.0, Fide.k_factor(&history(2300.0, 29, 2300.0)));
+        assert_eq!(20.0, Fide.k_factor(&history(2300.0, 30, 2399.0)));
+        assert_eq!(10.0, Fide.k_factor(&history(2350.0, 200, 2400.0)));
+    }
+
+    #[test]
+    fn uscf() {
+        let uscf = Uscf::default();
+        // The effective number of games of a 1500 player is about 16.57.
+        let established = uscf.k_factor(&history(1500.0, 100, 1500.0));
+        assert!((established - 800.0 / (16.568 + 1.0)).abs() < 0.01);
+        // Stronger players have more effective games and a smaller K-factor.
+        assert!(uscf.k_factor(&history(2200.0, 100, 2200.0)) < established);
+        assert_eq!(uscf.k_factor(&history(2355.0, 100, 2355.0)),
+                   uscf.k_factor(&history(2600.0, 100, 2600.0)));
+        assert!((Uscf::new(4).k_factor(&history(1500.0, 6, 1500.0)) - 80.0).abs()
+                < 1e-9);
+    }
+
+    #[test]
+    fn rating_bands() {
+        let bands = RatingBands::new(vec![(2400.0, 16.0), (2100.0, 24.0)]);
+        assert_eq!(24.0, bands.k_factor(&history(1000.0, 0, 1000.0)));
+        assert_eq!(24.0, bands.k_factor(&history(2100.0, 0, 2100.0)));
+        assert_eq!(16.0, bands.k_factor(&history(2500.0, 0, 2500.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "RatingBands needs at least one band")]
+    fn no_rating_bands() {
+        RatingBands::<f64>::new(Vec::new());
+    }
+}
@@ -1,20 +1,80 @@
+#![allow(clippy::needless_return)]
+
+pub mod bootstrap;
+pub mod bradley_terry;
+pub mod fifa;
+pub mod football;
+pub mod glicko;
+pub mod glicko2;
+pub mod performance;
+pub mod trueskill;
+pub mod weng_lin;
+pub mod whr;
+
+mod expectation;
+mod float;
+mod k_factor;
+mod linear;
+mod margin;
+mod normal;
+mod outcome;
+mod prediction;
+
+pub use expectation::{ExpectationModel, Logistic, Normal};
+pub use float::{Float, IntoFloat};
+pub use k_factor::{rebalance, Fide, KFactorPolicy, PlayerHistory, RatingBands, UpdateMode,
+                   Uscf};
+pub use margin::{MarginOfVictory, NbaMargin, NflMargin};
+pub use outcome::{Error, MatchResult, Outcome, Side};
+pub use prediction::{DrawModel, Prediction, Stakes};
+
+use expectation::solve;
+
 /// Elo.
-pub trait Elo {
+///
+/// Ratings are `f32` unless another `Float` type is given.
+pub trait Elo<F: Float = f32> {
     /// Get the rating.
-    fn get_rating(&self) -> f32;
+    fn get_rating(&self) -> F;
     /// Set the rating.
-    fn change_rating(&mut self, rating: f32);
-}
-
-fn expected_rating<T: Elo>(player_one: &T, player_two: &T) -> f32 {
-    return 1.0f32 / (1.0f32 + 10f32.powf(
-        (player_two.get_rating() - player_one.get_rating()) / 400f32
-    ));
+    fn change_rating(&mut self, rating: F);
+    /// Get the number of rated games played, for `KFactorPolicy`. Players
+    /// count as new by default.
+    fn games_played(&self) -> usize {
+        return 0;
+    }
+    /// Get the highest rating ever reached, for `KFactorPolicy`. This is
+    /// the current rating by default.
+    fn peak_rating(&self) -> F {
+        return self.get_rating();
+    }
+    /// Shift the rating outside of a game, for `rebalance`. This is
+    /// `change_rating` by default; override it if `change_rating` counts
+    /// games or tracks the peak rating.
+    fn shift_rating(&mut self, shift: F) {
+        self.change_rating(shift);
+    }
+    /// Record games rated together with a single `change_rating`, for
+    /// `EloRanking::rating_period`: a player with m games in a period gets
+    /// one `change_rating` and `record_games(m - 1)`. Does nothing by
+    /// default; override it if `change_rating` counts games.
+    fn record_games(&mut self, _games: usize) {}
 }
 
 /// EloRanking.
-pub struct EloRanking {
-    k_factor: usize,
+///
+/// The type parameters are the `Float` type of the ratings, `f32` by
+/// default, and the `ExpectationModel`, the logistic curve by default.
+pub struct EloRanking<F = f32, M = Logistic<F>> {
+    k_factor: F,
+    k_factor_policy: Option<Box<dyn KFactorPolicy<F>>>,
+    update_mode: UpdateMode,
+    provisional_games: usize,
+    advantage: F,
+    margin_of_victory: Option<Box<dyn MarginOfVictory<F>>>,
+    model: M,
+    draw_model: DrawModel<F>,
+    draw_updates: bool,
 }
 
 impl EloRanking {
@@ -26,13 +86,119 @@ impl EloRanking {
     /// # use elo::EloRanking;
     /// let k_factor: usize = 32;
     /// let elo_ranking = EloRanking::new(k_factor);
+    /// let tuned = EloRanking::new(10.5);
+    /// ```
+    pub fn new<K: IntoFloat>(k: K) -> EloRanking {
+        return EloRanking::from_k_factor(k);
+    }
+}
+
+impl<F: Float> EloRanking<F> {
+    /// Create a new Elo ranking system for any `Float` type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let elo_ranking = EloRanking::<f64>::from_k_factor(32);
+    /// ```
+    pub fn from_k_factor<K: IntoFloat>(k: K) -> EloRanking<F> {
+        return EloRanking {
+            k_factor: k.into_float(),
+            k_factor_policy: None,
+            update_mode: UpdateMode::Symmetric,
+            provisional_games: 0,
+            advantage: F::from_f64(0.0),
+            margin_of_victory: None,
+            model: Logistic::default(),
+            draw_model: DrawModel::NoDraws,
+            draw_updates: false,
+        }
+    }
+
+    /// Change the base of the expectation curve.
+    ///
+    /// A player rated one scale above another is expected to score `base`
+    /// times as much. The default base is 10.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// # let mut elo_ranking = EloRanking::new(32);
+    /// elo_ranking.set_base(std::f32::consts::E);
+    /// elo_ranking.set_scale(173.7);
+    /// ```
+    pub fn set_base(&mut self, base: F) {
+        self.model.set_base(base);
+    }
+
+    /// Returns the base of the expectation curve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// # let elo_ranking = EloRanking::new(32);
+    /// assert_eq!(10.0, elo_ranking.get_base());
+    /// ```
+    pub fn get_base(&self) -> F {
+        return self.model.get_base();
+    }
+
+    /// Change the scale of the expectation curve, the rating difference at
+    /// which the odds are `base` to 1. The default scale is 400.
+    pub fn set_scale(&mut self, scale: F) {
+        self.model.set_scale(scale);
+    }
+
+    /// Returns the scale of the expectation curve.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// # let elo_ranking = EloRanking::new(32);
+    /// assert_eq!(400.0, elo_ranking.get_scale());
     /// ```
-    pub fn new(k: usize) -> EloRanking {
+    pub fn get_scale(&self) -> F {
+        return self.model.get_scale();
+    }
+}
+
+impl<F: Float, M: ExpectationModel<F>> EloRanking<F, M> {
+    /// Use another expectation model.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{EloRanking, Normal};
+    /// let elo_ranking = EloRanking::new(32).with_model(Normal::default());
+    /// ```
+    pub fn with_model<N: ExpectationModel<F>>(self, model: N) -> EloRanking<F, N> {
         return EloRanking {
-            k_factor: k,
+            k_factor: self.k_factor,
+            k_factor_policy: self.k_factor_policy,
+            update_mode: self.update_mode,
+            provisional_games: self.provisional_games,
+            advantage: self.advantage,
+            margin_of_victory: self.margin_of_victory,
+            model,
+            draw_model: self.draw_model,
+            draw_updates: self.draw_updates,
         }
     }
 
+    /// Change the parameters of the expectation model.
+    pub fn set_model(&mut self, model: M) {
+        self.model = model;
+    }
+
+    /// Returns the expectation model.
+    pub fn get_model(&self) -> &M {
+        return &self.model;
+    }
+
     /// Change the K factor.
     ///
     /// # Example
@@ -41,13 +207,17 @@ impl EloRanking {
     /// # use elo::EloRanking;
     /// # let mut elo_ranking = EloRanking::new(32);
     /// elo_ranking.set_k_factor(25);
+    /// elo_ranking.set_k_factor(0.8);
     /// ```
-    pub fn set_k_factor(&mut self, k: usize) {
-        self.k_factor = k;
+    pub fn set_k_factor<K: IntoFloat>(&mut self, k: K) {
+        self.k_factor = k.into_float();
     }
 
     /// Returns the K factor.
     ///
+    /// The K factor is rounded to a whole number, as it was before
+    /// fractional K factors; use `get_exact_k_factor` for its exact value.
+    ///
     /// # Example
     ///
     /// ```
@@ -56,31 +226,879 @@ impl EloRanking {
     /// assert_eq!(32, elo_ranking.get_k_factor());
     /// ```
     pub fn get_k_factor(&self) -> usize {
+        return self.k_factor.to_f64().round().max(0.0) as usize;
+    }
+
+    /// Returns the exact K factor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// # let elo_ranking = EloRanking::new(10.5);
+    /// assert_eq!(10.5, elo_ranking.get_exact_k_factor());
+    /// ```
+    pub fn get_exact_k_factor(&self) -> F {
         return self.k_factor;
     }
 
+    /// Choose the K factor of every player with a policy instead of using
+    /// the same K factor for everyone.
+    ///
+    /// By default both ratings change by the same amount, using the mean of
+    /// the K factors of both players. See `set_update_mode`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{EloRanking, Fide};
+    /// # let mut elo_ranking = EloRanking::new(32);
+    /// elo_ranking.set_k_factor_policy(Fide);
+    /// ```
+    pub fn set_k_factor_policy<P>(&mut self, policy: P)
+        where P: KFactorPolicy<F> + 'static {
+        self.k_factor_policy = Some(Box::new(policy));
+    }
+
+    /// Go back to the same K factor for everyone.
+    pub fn remove_k_factor_policy(&mut self) {
+        self.k_factor_policy = None;
+    }
+
+    /// Returns the K factor policy, if any.
+    pub fn get_k_factor_policy(&self) -> Option<&dyn KFactorPolicy<F>> {
+        return self.k_factor_policy.as_deref();
+    }
+
+    /// Change how the K factors of both players combine.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking, Fide, UpdateMode};
+    /// struct Player { rating: f32, games: usize }
+    /// impl Elo for Player {
+    ///     fn get_rating(&self) -> f32 { self.rating }
+    ///     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    ///     fn games_played(&self) -> usize { self.games }
+    /// }
+    /// let mut elo_ranking = EloRanking::new(32);
+    /// elo_ranking.set_k_factor_policy(Fide);
+    /// elo_ranking.set_update_mode(UpdateMode::Individual);
+    /// let mut newcomer = Player { rating: 1500.0, games: 0 };
+    /// let mut veteran = Player { rating: 1500.0, games: 100 };
+    /// elo_ranking.win(&mut newcomer, &mut veteran);
+    /// assert_eq!(1520.0, newcomer.rating);
+    /// assert_eq!(1490.0, veteran.rating);
+    /// ```
+    pub fn set_update_mode(&mut self, update_mode: UpdateMode) {
+        self.update_mode = update_mode;
+    }
+
+    /// Returns how the K factors of both players combine.
+    pub fn get_update_mode(&self) -> UpdateMode {
+        return self.update_mode;
+    }
+
+    /// Returns the K factor of a player.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking, Fide};
+    /// struct Player { rating: f32, games: usize }
+    /// impl Elo for Player {
+    ///     fn get_rating(&self) -> f32 { self.rating }
+    ///     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    ///     fn games_played(&self) -> usize { self.games }
+    /// }
+    /// let mut elo_ranking = EloRanking::new(32);
+    /// assert_eq!(32.0, elo_ranking.player_k_factor(&Player { rating: 1500.0, games: 0 }));
+    /// elo_ranking.set_k_factor_policy(Fide);
+    /// assert_eq!(40.0, elo_ranking.player_k_factor(&Player { rating: 1500.0, games: 0 }));
+    /// assert_eq!(20.0, elo_ranking.player_k_factor(&Player { rating: 1500.0, games: 30 }));
+    /// ```
+    pub fn player_k_factor<T: Elo<F>>(&self, player: &T) -> F {
+        return match self.k_factor_policy {
+            Some(ref policy) => policy.k_factor(&PlayerHistory {
+                rating: player.get_rating(),
+                games_played: player.games_played(),
+                peak_rating: player.peak_rating(),
+            }),
+            None => self.k_factor,
+        };
+    }
+
+    /// Make players provisional for their first games.
+    ///
+    /// The rating of a provisional player is a performance rating, from the
+    /// special formula of the USCF for a player with N previous games:
+    /// (R × N + opponent's rating + 400 × (wins - losses)) / (N + 1). The
+    /// initial rating of a new player therefore does not matter. Their
+    /// opponents are protected from large swings: an established player's
+    /// change is scaled by the fraction of provisional games the provisional
+    /// player has played, so nothing changes against a brand new player.
+    ///
+    /// This relies on `Elo::games_played`, and 0 games, the default, turns
+    /// provisional ratings off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking};
+    /// struct Player { rating: f32, games: usize }
+    /// impl Elo for Player {
+    ///     fn get_rating(&self) -> f32 { self.rating }
+    ///     fn change_rating(&mut self, rating: f32) {
+    ///         self.rating += rating;
+    ///         self.games += 1;
+    ///     }
+    ///     fn games_played(&self) -> usize { self.games }
+    ///     fn record_games(&mut self, games: usize) { self.games += games; }
+    /// }
+    /// let mut elo_ranking = EloRanking::new(32);
+    /// elo_ranking.set_provisional_games(20);
+    /// let mut newcomer = Player { rating: 1000.0, games: 0 };
+    /// let mut veteran = Player { rating: 1800.0, games: 100 };
+    /// elo_ranking.win(&mut newcomer, &mut veteran);
+    /// assert_eq!(2200.0, newcomer.rating);
+    /// assert_eq!(1800.0, veteran.rating);
+    /// ```
+    pub fn set_provisional_games(&mut self, games: usize) {
+        self.provisional_games = games;
+    }
+
+    /// Returns the number of games for which players are provisional.
+    pub fn get_provisional_games(&self) -> usize {
+        return self.provisional_games;
+    }
+
+    /// Returns whether a player is provisional.
+    pub fn is_provisional<T: Elo<F>>(&self, player: &T) -> bool {
+        return player.games_played() < self.provisional_games;
+    }
+
+    /// Change the rating bonus of the player with the advantage of playing
+    /// at home or moving first, which is added to their rating in the
+    /// expected score. The default is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking, MatchResult, Outcome, Side};
+    /// # struct Player { rating: f32 }
+    /// # impl Elo for Player {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let mut elo_ranking = EloRanking::new(20);
+    /// elo_ranking.set_advantage(100.0);
+    /// let mut players = vec![Player { rating: 1500.0 }, Player { rating: 1500.0 }];
+    /// let mut result = MatchResult::new(0, 1, Outcome::Draw);
+    /// result.advantage = Some(Side::PlayerOne);
+    /// elo_ranking.apply(&mut players, &result).unwrap();
+    /// // The home side was expected to win.
+    /// assert!(players[0].rating < 1500.0);
+    /// ```
+    pub fn set_advantage(&mut self, advantage: F) {
+        self.advantage = advantage;
+    }
+
+    /// Returns the rating bonus of the player with the advantage.
+    pub fn get_advantage(&self) -> F {
+        return self.advantage;
+    }
+
+    /// Scale the K factor of decisive games by a multiplier of the winning
+    /// margin, used by `rating_change_with_margin`, `win_by` and results
+    /// with a margin. Draws are not scaled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking, NflMargin};
+    /// # struct Team { rating: f32 }
+    /// # impl Elo for Team {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let mut elo_ranking = EloRanking::new(20);
+    /// elo_ranking.set_margin_of_victory(NflMargin);
+    /// let (close, _) = elo_ranking.rating_change_with_margin(
+    ///     &Team { rating: 1500.0 }, &Team { rating: 1500.0 }, 1.0, 3.0, None);
+    /// let (blowout, _) = elo_ranking.rating_change_with_margin(
+    ///     &Team { rating: 1500.0 }, &Team { rating: 1500.0 }, 1.0, 28.0, None);
+    /// assert!(blowout > close);
+    /// ```
+    pub fn set_margin_of_victory<P>(&mut self, margin_of_victory: P)
+        where P: MarginOfVictory<F> + 'static {
+        self.margin_of_victory = Some(Box::new(margin_of_victory));
+    }
+
+    /// Stop scaling the K factor by the winning margin.
+    pub fn remove_margin_of_victory(&mut self) {
+        self.margin_of_victory = None;
+    }
+
+    /// Returns the margin of victory multiplier, if any.
+    pub fn get_margin_of_victory(&self) -> Option<&dyn MarginOfVictory<F>> {
+        return self.margin_of_victory.as_deref();
+    }
+
+    /// Change the draw model used by `predict`.
+    ///
+    /// The draw model only changes rating updates once they are enabled with
+    /// `set_draw_updates`. The default `NoDraws` counts a draw as half a win.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{DrawModel, EloRanking};
+    /// # let mut elo_ranking = EloRanking::new(32);
+    /// elo_ranking.set_draw_model(DrawModel::Davidson(0.5));
+    /// assert_eq!(DrawModel::Davidson(0.5), elo_ranking.get_draw_model());
+    /// ```
+    pub fn set_draw_model(&mut self, draw_model: DrawModel<F>) {
+        self.draw_model = draw_model;
+    }
+
+    /// Returns the draw model.
+    pub fn get_draw_model(&self) -> DrawModel<F> {
+        return self.draw_model;
+    }
+
+    /// Change whether rating updates follow the likelihoods of the draw
+    /// model.
+    ///
+    /// When enabled, the expected score of an update is the probability of a
+    /// win plus half the probability of a draw. Davidson's draw probability,
+    /// like the Elo curve, only depends on the rating difference: it is
+    /// highest between equal players and pulls the expected score of uneven
+    /// games towards one half, so a draw costs the stronger player less than
+    /// without a draw model. `DrawModel::DavidsonByLevel` also makes draws
+    /// more or less likely with the mean rating of the players, and pulls
+    /// the expected score further towards one half where draws are more
+    /// common. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{DrawModel, Elo, EloRanking};
+    /// # struct Player { rating: f64 }
+    /// # impl Elo<f64> for Player {
+    /// #     fn get_rating(&self) -> f64 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f64) { self.rating += rating; }
+    /// # }
+    /// let mut elo_ranking = EloRanking::from_k_factor(32);
+    /// elo_ranking.set_draw_model(DrawModel::Davidson(1.0));
+    /// elo_ranking.set_draw_updates(true);
+    /// let mut favorite = Player { rating: 1700.0 };
+    /// let mut underdog = Player { rating: 1500.0 };
+    /// elo_ranking.tie(&mut favorite, &mut underdog);
+    /// assert!(favorite.rating > 1700.0 - 7.7);
+    /// ```
+    pub fn set_draw_updates(&mut self, draw_updates: bool) {
+        self.draw_updates = draw_updates;
+    }
+
+    /// Returns whether rating updates follow the likelihoods of the draw
+    /// model.
+    pub fn get_draw_updates(&self) -> bool {
+        return self.draw_updates;
+    }
+
+    /// Returns the win, draw and loss probabilities of player one in a game
+    /// against player two, without changing either rating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{DrawModel, Elo, EloRanking};
+    /// # struct Player { rating: f32 }
+    /// # impl Elo for Player {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let mut elo_ranking = EloRanking::new(32);
+    /// elo_ranking.set_draw_model(DrawModel::RaoKupper(1.5));
+    /// let prediction = elo_ranking.predict(&Player { rating: 1600.0 },
+    ///                                      &Player { rating: 1500.0 });
+    /// assert!(prediction.win > prediction.loss);
+    /// assert!(prediction.draw > 0.0);
+    /// ```
+    pub fn predict<T: Elo<F>>(&self, player_one: &T, player_two: &T) -> Prediction<F> {
+        return self.predict_with_advantage(player_one, player_two, None);
+    }
+
+    /// Returns the win, draw and loss probabilities of player one, with the
+    /// bonus of `set_advantage` for one of the players.
+    pub fn predict_with_advantage<T: Elo<F>>(&self,
+                                             player_one: &T,
+                                             player_two: &T,
+                                             advantage: Option<Side>) -> Prediction<F> {
+        let expected = self.expected_rating(player_one, player_two, advantage);
+        let level = (player_one.get_rating() + player_two.get_rating()) /
+            F::from_f64(2.0);
+        return self.draw_model.probabilities_at(expected, level);
+    }
+
+    /// Fit Davidson's draw model to historical games.
+    ///
+    /// Each game is given as the ratings of both players and the score of
+    /// the first one. See `DrawModel::fit_davidson`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let mut elo_ranking = EloRanking::new(32);
+    /// let games = [(1500.0, 1500.0, 0.5), (1600.0, 1500.0, 1.0),
+    ///              (1400.0, 1500.0, 0.5), (1500.0, 1700.0, 0.0)];
+    /// let draw_model = elo_ranking.fit_davidson(&games);
+    /// elo_ranking.set_draw_model(draw_model);
+    /// ```
+    pub fn fit_davidson(&self, games: &[(F, F, F)]) -> DrawModel<F> {
+        let games: Vec<(F, F)> = games.iter()
+            .map(|&(rating_one, rating_two, score)| {
+                (self.model.expected_score(rating_one - rating_two), score)
+            })
+            .collect();
+        return DrawModel::fit_davidson(&games);
+    }
+
+    /// Fit Davidson's draw model with a draw parameter that depends on the
+    /// mean rating of the players to historical games.
+    ///
+    /// Each game is given as the ratings of both players and the score of
+    /// the first one. See `DrawModel::fit_davidson_by_level`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{DrawModel, EloRanking};
+    /// let mut elo_ranking = EloRanking::<f64>::from_k_factor(32);
+    /// let games = [(1500.0, 1500.0, 0.5), (1500.0, 1500.0, 1.0),
+    ///              (1500.0, 1500.0, 0.0), (2500.0, 2500.0, 0.5),
+    ///              (2500.0, 2500.0, 0.5), (2500.0, 2500.0, 1.0)];
+    /// let draw_model = elo_ranking.fit_davidson_by_level(&games);
+    /// elo_ranking.set_draw_model(draw_model);
+    /// elo_ranking.set_draw_updates(true);
+    /// match draw_model {
+    ///     DrawModel::DavidsonByLevel(_, slope) => assert!(slope > 0.0),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn fit_davidson_by_level(&self, games: &[(F, F, F)]) -> DrawModel<F> {
+        let games: Vec<(F, F, F)> = games.iter()
+            .map(|&(rating_one, rating_two, score)| {
+                (self.model.expected_score(rating_one - rating_two),
+                 (rating_one + rating_two) / F::from_f64(2.0),
+                 score)
+            })
+            .collect();
+        return DrawModel::fit_davidson_by_level(&games);
+    }
+
+    /// Returns the rating difference at which player one is expected to
+    /// score `expected` against player two, the inverse of the expectation
+    /// curve. Scores of 1 and 0 give infinite differences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let elo_ranking = EloRanking::<f64>::from_k_factor(32);
+    /// assert_eq!(191.0, elo_ranking.rating_difference(0.75).round());
+    /// assert_eq!(std::f64::INFINITY, elo_ranking.rating_difference(1.0));
+    /// ```
+    pub fn rating_difference(&self, expected: F) -> F {
+        return self.model.rating_difference(expected);
+    }
+
+    /// Returns the odds of player one against player two for a rating
+    /// difference: the expected score of player one divided by that of
+    /// player two.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let elo_ranking = EloRanking::<f64>::from_k_factor(32);
+    /// assert!((elo_ranking.odds(400.0) - 10.0).abs() < 1e-9);
+    /// ```
+    pub fn odds(&self, difference: F) -> F {
+        let expected = self.model.expected_score(difference);
+        return expected / (F::from_f64(1.0) - expected);
+    }
+
+    /// Returns the rating difference for odds of player one against player
+    /// two, the inverse of `odds`. Odds of 0 and infinite odds give infinite
+    /// differences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let elo_ranking = EloRanking::<f64>::from_k_factor(32);
+    /// assert!((elo_ranking.odds_difference(3.0) - 190.85).abs() < 0.01);
+    /// ```
+    pub fn odds_difference(&self, odds: F) -> F {
+        let one = F::from_f64(1.0);
+        let expected = if odds == F::from_f64(f64::INFINITY) {
+            one
+        } else {
+            odds / (one + odds)
+        };
+        return self.model.rating_difference(expected);
+    }
+
+    /// Returns the performance rating over some games: the rating at which
+    /// the total score against the opponents would have been expected.
+    ///
+    /// This is the exact inverse of the expectation curve, solved
+    /// numerically. A perfect or zero score has no finite performance
+    /// rating, so it returns `None`, as does an empty list of games. See
+    /// `performance` for the approximations used by federations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let elo_ranking = EloRanking::<f64>::from_k_factor(32);
+    /// let opponents = [2400.0, 2500.0, 2600.0, 2500.0];
+    /// let performance = elo_ranking.performance_rating(&opponents, 3.0).unwrap();
+    /// assert!((performance - 2697.9).abs() < 0.1);
+    /// assert_eq!(None, elo_ranking.performance_rating(&opponents, 4.0));
+    /// ```
+    pub fn performance_rating(&self, opponent_ratings: &[F], score: F) -> Option<F> {
+        let zero = F::from_f64(0.0);
+        if opponent_ratings.is_empty() || score <= zero ||
+            score >= F::from_f64(opponent_ratings.len() as f64) {
+            return None;
+        }
+        let expected = |rating: F| {
+            let mut total = zero;
+            for &opponent in opponent_ratings {
+                total = total + self.model.expected_score(rating - opponent);
+            }
+            return total;
+        };
+        let mut lower = opponent_ratings[0];
+        let mut upper = opponent_ratings[0];
+        for &opponent in opponent_ratings {
+            if opponent < lower {
+                lower = opponent;
+            }
+            if opponent > upper {
+                upper = opponent;
+            }
+        }
+        return Some(solve(expected, score, lower, upper));
+    }
+
+    /// Estimate the rating bonus of `set_advantage` from historical games.
+    ///
+    /// Each game is given as the ratings of the player with the advantage
+    /// and of their opponent, and the score of the player with the
+    /// advantage. The estimate is the bonus at which the total score would
+    /// have been expected, using the expectation model and the expected
+    /// score of the draw model. With the logistic curve and `NoDraws` this
+    /// is the maximum likelihood estimate. If the players with the advantage
+    /// scored everything or nothing, it is infinite, and without any games
+    /// it is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::EloRanking;
+    /// let mut elo_ranking = EloRanking::<f64>::from_k_factor(20);
+    /// let games = [(1500.0, 1500.0, 1.0), (1500.0, 1500.0, 0.5),
+    ///              (1600.0, 1500.0, 1.0), (1500.0, 1600.0, 0.0)];
+    /// let advantage = elo_ranking.fit_advantage(&games);
+    /// assert!(advantage > 0.0);
+    /// elo_ranking.set_advantage(advantage);
+    /// ```
+    pub fn fit_advantage(&self, games: &[(F, F, F)]) -> F {
+        let zero = F::from_f64(0.0);
+        if games.is_empty() {
+            return zero;
+        }
+        let mut score = zero;
+        for &(_, _, game_score) in games {
+            score = score + game_score;
+        }
+        let expected = |advantage: F| {
+            let mut total = zero;
+            for &(rating, opponent, _) in games {
+                let expected = self.model.expected_score(rating - opponent + advantage);
+                let level = (rating + opponent) / F::from_f64(2.0);
+                total = total +
+                    self.draw_model.probabilities_at(expected, level).expected_score();
+            }
+            return total;
+        };
+        if score <= zero {
+            return F::from_f64(f64::NEG_INFINITY);
+        }
+        if score >= F::from_f64(games.len() as f64) {
+            return F::from_f64(f64::INFINITY);
+        }
+        return solve(expected, score, F::from_f64(-100.0), F::from_f64(100.0));
+    }
+
+    /// Internal method for the expected score of player one.
+    fn expected_rating<T: Elo<F>>(&self,
+                                  player_one: &T,
+                                  player_two: &T,
+                                  advantage: Option<Side>) -> F {
+        return self.model.expected_score(
+            player_one.get_rating() - player_two.get_rating() +
+                self.advantage_of(advantage)
+        );
+    }
+
+    /// Internal method for the rating bonus of player one.
+    fn advantage_of(&self, advantage: Option<Side>) -> F {
+        return match advantage {
+            Some(Side::PlayerOne) => self.advantage,
+            Some(Side::PlayerTwo) => -self.advantage,
+            None => F::from_f64(0.0),
+        };
+    }
+
+    /// Returns the rating changes of player one and player two for a score
+    /// of player one, without changing either rating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking};
+    /// # struct Player { rating: f32 }
+    /// # impl Elo for Player {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let elo_ranking = EloRanking::new(32);
+    /// let player_one = Player { rating: 1400.0 };
+    /// let player_two = Player { rating: 1400.0 };
+    /// let (one, two) = elo_ranking.rating_change(&player_one, &player_two, 0.75);
+    /// assert_eq!((8.0, -8.0), (one, two));
+    /// ```
+    pub fn rating_change<T: Elo<F>>(&self,
+                                    player_one: &T,
+                                    player_two: &T,
+                                    score: F) -> (F, F) {
+        return self.rating_change_with_advantage(player_one, player_two, score, None);
+    }
+
+    /// Returns the rating changes of player one and player two for a score
+    /// of player one, with the bonus of `set_advantage` for one of the
+    /// players.
+    pub fn rating_change_with_advantage<T: Elo<F>>(&self,
+                                                   player_one: &T,
+                                                   player_two: &T,
+                                                   score: F,
+                                                   advantage: Option<Side>) -> (F, F) {
+        return self.change(player_one, player_two, score, advantage, None);
+    }
+
+    /// Returns the rating changes of player one and player two for a score
+    /// of player one won by a margin, with the bonus of `set_advantage` for
+    /// one of the players if any. The K factor is scaled by the multiplier
+    /// of `set_margin_of_victory`.
+    pub fn rating_change_with_margin<T: Elo<F>>(&self,
+                                                player_one: &T,
+                                                player_two: &T,
+                                                score: F,
+                                                margin: F,
+                                                advantage: Option<Side>) -> (F, F) {
+        return self.change(player_one, player_two, score, advantage, Some(margin));
+    }
+
+    /// Internal method for the rating changes of a game.
+    fn change<T: Elo<F>>(&self,
+                         player_one: &T,
+                         player_two: &T,
+                         score: F,
+                         advantage: Option<Side>,
+                         margin: Option<F>) -> (F, F) {
+        let bonus = self.advantage_of(advantage);
+        let provisional_one = self.is_provisional(player_one);
+        let provisional_two = self.is_provisional(player_two);
+        if !provisional_one && !provisional_two {
+            return self.established_change(player_one, player_two, score,
+                                           advantage, margin);
+        }
+        let (change_one, change_two) =
+            self.established_change(player_one, player_two, score, advantage, margin);
+        let change_one = if provisional_one {
+            self.provisional_change(player_one, player_two, score, bonus)
+        } else {
+            change_one * self.provisional_weight(player_two)
+        };
+        let change_two = if provisional_two {
+            self.provisional_change(player_two, player_one,
+                                    F::from_f64(1.0) - score, -bonus)
+        } else {
+            change_two * self.provisional_weight(player_one)
+        };
+        return (change_one, change_two);
+    }
+
+    /// Internal method for the rating change of a provisional player with a
+    /// rating bonus.
+    fn provisional_change<T: Elo<F>>(&self,
+                                     player: &T,
+                                     opponent: &T,
+                                     score: F,
+                                     bonus: F) -> F {
+        let performance = self.performance(opponent, score, bonus);
+        return self.provisional_total(player, performance, 1);
+    }
+
+    /// Internal method for the performance of a provisional player in one
+    /// game: the opponent's rating, less the player's bonus, plus 400 for a
+    /// win and minus 400 for a loss.
+    fn performance<T: Elo<F>>(&self, opponent: &T, score: F, bonus: F) -> F {
+        return opponent.get_rating() - bonus +
+            F::from_f64(400.0) * (F::from_f64(2.0) * score - F::from_f64(1.0));
+    }
+
+    /// Internal method for the rating change of a provisional player from
+    /// the sum of their performances in some games, with the special
+    /// formula of the USCF.
+    fn provisional_total<T: Elo<F>>(&self, player: &T, performances: F, games: usize) -> F {
+        let played = F::from_f64(player.games_played() as f64);
+        let rating = player.get_rating();
+        return (rating * played + performances) /
+            (played + F::from_f64(games as f64)) - rating;
+    }
+
+    /// Internal method for how much a game against a provisional player
+    /// counts for an established opponent.
+    fn provisional_weight<T: Elo<F>>(&self, player: &T) -> F {
+        return F::from_f64(player.games_played() as f64 /
+                           self.provisional_games as f64);
+    }
+
+    /// Internal method for the rating changes of established players.
+    fn established_change<T: Elo<F>>(&self,
+                                     player_one: &T,
+                                     player_two: &T,
+                                     score: F,
+                                     advantage: Option<Side>,
+                                     margin: Option<F>) -> (F, F) {
+        let expected = if self.draw_updates {
+            self.predict_with_advantage::<T>(player_one, player_two, advantage)
+                .expected_score()
+        } else {
+            self.expected_rating(player_one, player_two, advantage)
+        };
+        let surprise = match (margin, self.get_margin_of_victory()) {
+            (Some(margin), Some(margin_of_victory)) => {
+                let half = F::from_f64(0.5);
+                let difference = player_one.get_rating() - player_two.get_rating() +
+                    self.advantage_of(advantage);
+                let multiplier = if score > half {
+                    margin_of_victory.multiplier(margin, difference)
+                } else if score < half {
+                    margin_of_victory.multiplier(margin, -difference)
+                } else {
+                    F::from_f64(1.0)
+                };
+                (score - expected) * multiplier
+            },
+            _ => score - expected,
+        };
+        if self.k_factor_policy.is_none() {
+            let change = self.k_factor * surprise;
+            return (change, -change);
+        }
+        let k_factor_one = self.player_k_factor(player_one);
+        let k_factor_two = self.player_k_factor(player_two);
+        return match self.update_mode {
+            UpdateMode::Symmetric => {
+                let change = (k_factor_one + k_factor_two) / F::from_f64(2.0) *
+                    surprise;
+                (change, -change)
+            },
+            UpdateMode::Individual => {
+                (k_factor_one * surprise, -(k_factor_two * surprise))
+            },
+        };
+    }
+
+    /// Returns the rating changes of both players for a win, tie and loss of
+    /// player one, without changing either rating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking};
+    /// # struct Player { rating: f32 }
+    /// # impl Elo for Player {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let elo_ranking = EloRanking::new(32);
+    /// let stakes = elo_ranking.stakes(&Player { rating: 1400.0 },
+    ///                                 &Player { rating: 1400.0 });
+    /// assert_eq!((16.0, -16.0), stakes.win);
+    /// assert_eq!((-16.0, 16.0), stakes.loss);
+    /// ```
+    pub fn stakes<T: Elo<F>>(&self, player_one: &T, player_two: &T) -> Stakes<F> {
+        return Stakes {
+            win: self.rating_change(player_one, player_two, F::from_f64(1.0)),
+            tie: self.rating_change(player_one, player_two, F::from_f64(0.5)),
+            loss: self.rating_change(player_one, player_two, F::from_f64(0.0)),
+        };
+    }
+
     /// Internal method for generic calculations.
-    fn calculate_rating<T: Elo>(&self,
-                                      player_one: &mut T,
-                                      player_two: &mut T,
-                                      score: f32) {
-        let change = self.k_factor as f32 *
-            (score - expected_rating::<T>(player_one, player_two));
-        player_one.change_rating(change);
-        player_two.change_rating(-change);
+    fn calculate_rating<T: Elo<F>>(&self,
+                                   player_one: &mut T,
+                                   player_two: &mut T,
+                                   score: F,
+                                   advantage: Option<Side>,
+                                   margin: Option<F>) {
+        let (change_one, change_two) =
+            self.change::<T>(player_one, player_two, score, advantage, margin);
+        player_one.change_rating(change_one);
+        player_two.change_rating(change_two);
     }
 
-    pub fn win<T: Elo>(&self, winner: &mut T, loser: &mut T) {
-        self.calculate_rating(winner, loser, 1.0);
+    pub fn win<T: Elo<F>>(&self, winner: &mut T, loser: &mut T) {
+        self.calculate_rating(winner, loser, F::from_f64(1.0), None, None);
     }
 
-    pub fn tie<T: Elo>(&self, player_one: &mut T, player_two: &mut T) {
-        self.calculate_rating(player_one, player_two, 0.5);
+    pub fn tie<T: Elo<F>>(&self, player_one: &mut T, player_two: &mut T) {
+        self.calculate_rating(player_one, player_two, F::from_f64(0.5), None, None);
     }
 
-    pub fn loss<T: Elo>(&self, loser: &mut T, winner: &mut T) {
+    pub fn loss<T: Elo<F>>(&self, loser: &mut T, winner: &mut T) {
         self.win::<T>(winner, loser);
     }
+
+    /// Rate a win by a margin, scaling the K factor by the multiplier of
+    /// `set_margin_of_victory`.
+    pub fn win_by<T: Elo<F>>(&self, winner: &mut T, loser: &mut T, margin: F) {
+        self.calculate_rating(winner, loser, F::from_f64(1.0), None, Some(margin));
+    }
+
+    /// Apply the result of a game between two of the players.
+    ///
+    /// The ratings are left unchanged if the result is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking, Error, MatchResult, Outcome};
+    /// # struct Player { rating: f32 }
+    /// # impl Elo for Player {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let elo_ranking = EloRanking::new(32);
+    /// let mut players = vec![Player { rating: 1400.0 }, Player { rating: 1400.0 }];
+    /// let result = MatchResult::new(1, 0, Outcome::Win);
+    /// elo_ranking.apply(&mut players, &result).unwrap();
+    /// assert_eq!(1416.0, players[1].rating);
+    /// let result = MatchResult::new(0, 1, Outcome::Partial(1.5));
+    /// assert_eq!(Err(Error::InvalidScore(1.5)), elo_ranking.apply(&mut players, &result));
+    /// ```
+    pub fn apply<T: Elo<F>>(&self,
+                            players: &mut [T],
+                            result: &MatchResult<F>) -> Result<(), Error> {
+        result.validate(players.len())?;
+        let score = result.outcome.score()?;
+        let (player_one, player_two) =
+            outcome::pair(players, result.player_one, result.player_two);
+        self.calculate_rating(player_one, player_two, score, result.advantage,
+                              result.margin);
+        return Ok(());
+    }
+
+    /// Apply the results of a rating period.
+    ///
+    /// Every game is rated against the ratings at the start of the period,
+    /// and the total change of every player who played is applied once at
+    /// the end. A provisional player is rated once with the special formula
+    /// over all of their games in the period. A player who played m games
+    /// gets one `Elo::change_rating` followed by `Elo::record_games(m - 1)`.
+    /// The ratings are left unchanged if any result is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{Elo, EloRanking, MatchResult, Outcome};
+    /// # struct Player { rating: f32 }
+    /// # impl Elo for Player {
+    /// #     fn get_rating(&self) -> f32 { self.rating }
+    /// #     fn change_rating(&mut self, rating: f32) { self.rating += rating; }
+    /// # }
+    /// let elo_ranking = EloRanking::new(32);
+    /// let mut players = vec![Player { rating: 1400.0 }, Player { rating: 1400.0 }];
+    /// let results = [MatchResult::new(0, 1, Outcome::Win),
+    ///                MatchResult::new(0, 1, Outcome::Loss)];
+    /// elo_ranking.rating_period(&mut players, &results).unwrap();
+    /// assert_eq!(1400.0, players[0].rating);
+    /// ```
+    pub fn rating_period<T: Elo<F>>(&self,
+                                    players: &mut [T],
+                                    results: &[MatchResult<F>]) -> Result<(), Error> {
+        for result in results {
+            result.validate(players.len())?;
+        }
+        let zero = F::from_f64(0.0);
+        let mut changes: Vec<Option<F>> = vec![None; players.len()];
+        // The sum of the performances and the number of games of every
+        // provisional player.
+        let mut performances = vec![(zero, 0); players.len()];
+        let mut games_played = vec![0; players.len()];
+        for result in results {
+            let (one, two) = (result.player_one, result.player_two);
+            games_played[one] += 1;
+            games_played[two] += 1;
+            let score = result.outcome.score()?;
+            let bonus = self.advantage_of(result.advantage);
+            let (change_one, change_two) = self.change(
+                &players[one],
+                &players[two],
+                score,
+                result.advantage,
+                result.margin
+            );
+            for &(player, opponent, change, score, bonus) in
+                &[(one, two, change_one, score, bonus),
+                  (two, one, change_two, F::from_f64(1.0) - score, -bonus)] {
+                if self.is_provisional(&players[player]) {
+                    let (total, games) = performances[player];
+                    performances[player] =
+                        (total + self.performance(&players[opponent], score, bonus),
+                         games + 1);
+                    continue;
+                }
+                changes[player] = Some(match changes[player] {
+                    Some(total) => total + change,
+                    None => change,
+                });
+            }
+        }
+        for (player, &(total, games)) in performances.iter().enumerate() {
+            if games > 0 {
+                changes[player] =
+                    Some(self.provisional_total(&players[player], total, games));
+            }
+        }
+        let updates = players.iter_mut().zip(changes).zip(games_played);
+        for ((player, change), games) in updates {
+            if let Some(change) = change {
+                player.change_rating(change);
+                player.record_games(games - 1);
+            }
+        }
+        return Ok(());
+    }
 }
 
 #[cfg(test)]
@@ -132,4 +1150,505 @@ mod tests {
         assert_eq!(1398.5305f32, player_one.get_rating());
         assert_eq!(1401.4695f32, player_two.get_rating());
     }
+
+    struct Precise {
+        rating: f64,
+    }
+
+    impl Elo<f64> for Precise {
+        fn get_rating(&self) -> f64 {
+            return self.rating;
+        }
+        fn change_rating(&mut self, rating: f64) {
+            self.rating += rating;
+        }
+    }
+
+    /// Ratings in whole thousandths of a point.
+    #[derive(Clone, Copy, PartialEq, PartialOrd)]
+    struct Milli(f64);
+
+    impl ::std::ops::Add for Milli {
+        type Output = Milli;
+        fn add(self, other: Milli) -> Milli {
+            return Milli(self.0 + other.0);
+        }
+    }
+
+    impl ::std::ops::Sub for Milli {
+        type Output = Milli;
+        fn sub(self, other: Milli) -> Milli {
+            return Milli(self.0 - other.0);
+        }
+    }
+
+    impl ::std::ops::Mul for Milli {
+        type Output = Milli;
+        fn mul(self, other: Milli) -> Milli {
+            return Milli(self.0 * other.0);
+        }
+    }
+
+    impl ::std::ops::Div for Milli {
+        type Output = Milli;
+        fn div(self, other: Milli) -> Milli {
+            return Milli(self.0 / other.0);
+        }
+    }
+
+    impl ::std::ops::Neg for Milli {
+        type Output = Milli;
+        fn neg(self) -> Milli {
+            return Milli(-self.0);
+        }
+    }
+
+    impl Float for Milli {
+        fn from_f64(value: f64) -> Milli {
+            return Milli(value);
+        }
+        fn to_f64(self) -> f64 {
+            return self.0;
+        }
+        fn powf(self, exponent: Milli) -> Milli {
+            return Milli(self.0.powf(exponent.0));
+        }
+        fn exp(self) -> Milli {
+            return Milli(self.0.exp());
+        }
+        fn ln(self) -> Milli {
+            return Milli(self.0.ln());
+        }
+        fn sqrt(self) -> Milli {
+            return Milli(self.0.sqrt());
+        }
+        fn abs(self) -> Milli {
+            return Milli(self.0.abs());
+        }
+    }
+
+    struct Rounded {
+        rating: Milli,
+    }
+
+    impl Elo<Milli> for Rounded {
+        fn get_rating(&self) -> Milli {
+            return self.rating;
+        }
+        fn change_rating(&mut self, rating: Milli) {
+            self.rating = Milli(((self.rating.0 + rating.0) * 1000.0).round()
+                                / 1000.0);
+        }
+    }
+
+    #[test]
+    fn scale() {
+        let mut natural_system = EloRanking::<f64>::from_k_factor(32);
+        natural_system.set_base(::std::f64::consts::E);
+        natural_system.set_scale(400.0 / ::std::f64::consts::LN_10);
+        let mut player_one = Precise { rating: 1400.0 };
+        let mut player_two = Precise { rating: 1400.0 };
+        natural_system.win::<Precise>(&mut player_one, &mut player_two);
+        natural_system.loss::<Precise>(&mut player_one, &mut player_two);
+        // The natural log scale is the default scale in other units.
+        assert!((player_one.get_rating() - 1398.5304985).abs() < 1e-6);
+
+        let mut wide_system = EloRanking::<f64>::from_k_factor(32);
+        wide_system.set_scale(800.0);
+        let mut player_one = Precise { rating: 1600.0 };
+        let mut player_two = Precise { rating: 1400.0 };
+        wide_system.tie::<Precise>(&mut player_one, &mut player_two);
+        // With a scale of 800, 200 points are a quarter of the way to 10:1.
+        let expected = 1.0 / (1.0 + 10f64.powf(-0.25));
+        assert!((player_one.get_rating() - (1600.0 + 32.0 * (0.5 - expected)))
+                .abs() < 1e-9);
+    }
+
+    /// Expected score rising linearly over 800 points.
+    struct Linear;
+
+    impl ExpectationModel<f64> for Linear {
+        fn expected_score(&self, difference: f64) -> f64 {
+            return (0.5 + difference / 800.0).clamp(0.0, 1.0);
+        }
+    }
+
+    #[test]
+    fn expectation_models() {
+        let rating_system = EloRanking::<f64>::from_k_factor(32)
+            .with_model(Normal::default());
+        let mut player_one = Precise { rating: 1682.842712 };
+        let mut player_two = Precise { rating: 1400.0 };
+        rating_system.tie::<Precise>(&mut player_one, &mut player_two);
+        // One standard deviation ahead, so expected to score 84%.
+        assert!((player_one.get_rating() - (1682.842712 + 32.0 * (0.5 - 0.8413447)))
+                .abs() < 1e-4);
+
+        let rating_system = EloRanking::<f64>::from_k_factor(32)
+            .with_model(Linear);
+        let mut player_one = Precise { rating: 1600.0 };
+        let mut player_two = Precise { rating: 1400.0 };
+        rating_system.win::<Precise>(&mut player_one, &mut player_two);
+        assert_eq!(1608.0, player_one.get_rating());
+        assert_eq!(1392.0, player_two.get_rating());
+    }
+
+    #[test]
+    fn generic_float() {
+        let rating_system = EloRanking::<f64>::from_k_factor(32);
+        let mut player_one = Precise { rating: 1400.0 };
+        let mut player_two = Precise { rating: 1400.0 };
+        rating_system.win::<Precise>(&mut player_one, &mut player_two);
+        rating_system.loss::<Precise>(&mut player_one, &mut player_two);
+        assert!((player_one.get_rating() - 1398.5304985).abs() < 1e-6);
+        assert_eq!(2800.0, player_one.get_rating() + player_two.get_rating());
+
+        let rating_system = EloRanking::<Milli>::from_k_factor(32);
+        let mut player_one = Rounded { rating: Milli(1400.0) };
+        let mut player_two = Rounded { rating: Milli(1400.0) };
+        rating_system.win::<Rounded>(&mut player_one, &mut player_two);
+        rating_system.loss::<Rounded>(&mut player_one, &mut player_two);
+        assert_eq!(1398.53, player_one.get_rating().0);
+        assert_eq!(1401.47, player_two.get_rating().0);
+    }
+
+    #[test]
+    fn davidson() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(32);
+        rating_system.set_draw_model(DrawModel::Davidson(1.0));
+        // Without draw updates the draw model only changes predictions.
+        let mut player_one = Precise { rating: 1700.0 };
+        let mut player_two = Precise { rating: 1500.0 };
+        rating_system.tie::<Precise>(&mut player_one, &mut player_two);
+        let halved = 1700.0 - player_one.get_rating();
+        assert!((halved - 32.0 * (1.0 / (1.0 + 10f64.powf(-0.5)) - 0.5)).abs()
+                < 1e-9);
+
+        rating_system.set_draw_updates(true);
+        let mut player_one = Precise { rating: 1500.0 };
+        let mut player_two = Precise { rating: 1500.0 };
+        rating_system.tie::<Precise>(&mut player_one, &mut player_two);
+        assert_eq!(1500.0, player_one.get_rating());
+        rating_system.win::<Precise>(&mut player_one, &mut player_two);
+        assert_eq!(1516.0, player_one.get_rating());
+        assert_eq!(1484.0, player_two.get_rating());
+
+        // A draw costs the stronger player less than half a win would.
+        let mut player_one = Precise { rating: 1700.0 };
+        let mut player_two = Precise { rating: 1500.0 };
+        rating_system.tie::<Precise>(&mut player_one, &mut player_two);
+        let davidson = 1700.0 - player_one.get_rating();
+        assert!(davidson > 0.0);
+        assert!(davidson < halved);
+
+        // Where draws are more common, a draw costs the favorite even less.
+        rating_system.set_draw_model(DrawModel::DavidsonByLevel(-7.0, 0.004));
+        let mut weak = (Precise { rating: 1200.0 }, Precise { rating: 1000.0 });
+        let mut strong = (Precise { rating: 2700.0 }, Precise { rating: 2500.0 });
+        rating_system.tie::<Precise>(&mut weak.0, &mut weak.1);
+        rating_system.tie::<Precise>(&mut strong.0, &mut strong.1);
+        assert!(2700.0 - strong.0.get_rating() < 1200.0 - weak.0.get_rating());
+    }
+
+    #[test]
+    fn stakes() {
+        let rating_system = EloRanking::new(32);
+        let mut player_one = RatingObject { rating: 1500.0 };
+        let mut player_two = RatingObject { rating: 1400.0 };
+        let stakes = rating_system.stakes(&player_one, &player_two);
+        assert_eq!(1500.0, player_one.get_rating());
+        assert_eq!(stakes.win.0, -stakes.win.1);
+        assert!(stakes.win.0 < -stakes.loss.0);
+        assert!(stakes.tie.0 < 0.0);
+        rating_system.tie::<RatingObject>(&mut player_one, &mut player_two);
+        assert_eq!(1500.0 + stakes.tie.0, player_one.get_rating());
+        assert_eq!(1400.0 + stakes.tie.1, player_two.get_rating());
+    }
+
+    struct Veteran {
+        rating: f64,
+        games: usize,
+        peak: f64,
+    }
+
+    impl Elo<f64> for Veteran {
+        fn get_rating(&self) -> f64 {
+            return self.rating;
+        }
+        fn change_rating(&mut self, rating: f64) {
+            self.rating += rating;
+            self.games += 1;
+            if self.rating > self.peak {
+                self.peak = self.rating;
+            }
+        }
+        fn games_played(&self) -> usize {
+            return self.games;
+        }
+        fn peak_rating(&self) -> f64 {
+            return self.peak;
+        }
+        fn shift_rating(&mut self, shift: f64) {
+            self.rating += shift;
+        }
+        fn record_games(&mut self, games: usize) {
+            self.games += games;
+        }
+    }
+
+    #[test]
+    fn k_factor_policy() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(32);
+        rating_system.set_k_factor_policy(Fide);
+        let mut new = Veteran { rating: 1500.0, games: 0, peak: 1500.0 };
+        let mut master = Veteran { rating: 2300.0, games: 500, peak: 2410.0 };
+        assert_eq!(10.0, rating_system.player_k_factor(&master));
+        let (change, _) = rating_system.rating_change(&new, &master, 1.0);
+        rating_system.win::<Veteran>(&mut new, &mut master);
+        assert_eq!(1500.0 + change, new.get_rating());
+        assert_eq!(3800.0, new.get_rating() + master.get_rating());
+        // The mean of 40 and 10.
+        assert!((change - 25.0 * (1.0 - 1.0 / (1.0 + 10f64.powf(2.0)))).abs()
+                < 1e-9);
+        assert!(rating_system.get_k_factor_policy().is_some());
+        rating_system.remove_k_factor_policy();
+        assert_eq!(32.0, rating_system.player_k_factor(&master));
+    }
+
+    #[test]
+    fn fractional_k_factor() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(10.5);
+        assert_eq!(10.5, rating_system.get_exact_k_factor());
+        let mut player_one = Precise { rating: 1400.0 };
+        let mut player_two = Precise { rating: 1400.0 };
+        rating_system.win::<Precise>(&mut player_one, &mut player_two);
+        assert_eq!(1405.25, player_one.get_rating());
+        rating_system.set_k_factor(16u8);
+        assert_eq!(16.0, rating_system.get_exact_k_factor());
+        let rating_system = EloRanking::new(0.8f32);
+        assert_eq!(0.8f32, rating_system.get_exact_k_factor());
+        assert_eq!(1, rating_system.get_k_factor());
+    }
+
+    #[test]
+    fn individual_k_factors() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(32);
+        rating_system.set_k_factor_policy(Fide);
+        rating_system.set_update_mode(UpdateMode::Individual);
+        let mut players = vec![
+            Veteran { rating: 1600.0, games: 0, peak: 1600.0 },
+            Veteran { rating: 1400.0, games: 100, peak: 1400.0 },
+        ];
+        let expected = 1.0 / (1.0 + 10f64.powf(-0.5));
+        let (one, two) = rating_system.rating_change(&players[0], &players[1], 0.0);
+        assert!((one + 40.0 * expected).abs() < 1e-9);
+        assert!((two - 20.0 * expected).abs() < 1e-9);
+        rating_system.apply(&mut players, &MatchResult::new(0, 1, Outcome::Loss))
+            .unwrap();
+        // The pool lost rating points.
+        assert!(players[0].rating + players[1].rating < 3000.0);
+        rebalance(&mut players, 3000.0);
+        assert!((players[0].rating + players[1].rating - 3000.0).abs() < 1e-9);
+        // Rebalancing is not a game.
+        assert_eq!(1, players[0].games);
+        assert!((players[1].rating - players[0].rating -
+                 (two - one - 200.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn provisional() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(32);
+        rating_system.set_provisional_games(4);
+        let mut players = vec![
+            Veteran { rating: 1500.0, games: 0, peak: 1500.0 },
+            Veteran { rating: 1800.0, games: 50, peak: 1800.0 },
+            Veteran { rating: 1600.0, games: 50, peak: 1600.0 },
+        ];
+        assert!(rating_system.is_provisional(&players[0]));
+        assert!(!rating_system.is_provisional(&players[1]));
+        rating_system.apply(&mut players, &MatchResult::new(0, 1, Outcome::Loss))
+            .unwrap();
+        assert_eq!(1400.0, players[0].rating);
+        assert_eq!(1800.0, players[1].rating);
+        rating_system.apply(&mut players, &MatchResult::new(2, 0, Outcome::Draw))
+            .unwrap();
+        assert_eq!(1500.0, players[0].rating);
+        // A quarter of the established change.
+        let expected = 1.0 / (1.0 + 10f64.powf(-0.5));
+        assert!((players[2].rating - (1600.0 + 8.0 * (0.5 - expected))).abs()
+                < 1e-9);
+        // Two provisional players.
+        let mut one = Veteran { rating: 1500.0, games: 1, peak: 1500.0 };
+        let mut two = Veteran { rating: 1700.0, games: 3, peak: 1700.0 };
+        rating_system.win::<Veteran>(&mut one, &mut two);
+        assert_eq!((1500.0 + 2100.0) / 2.0, one.rating);
+        assert_eq!((1700.0 * 3.0 + 1100.0) / 4.0, two.rating);
+    }
+
+    #[test]
+    fn performance_rating() {
+        let rating_system = EloRanking::<f64>::from_k_factor(32);
+        let opponents = [1500.0, 1700.0, 1650.0];
+        let performance = rating_system.performance_rating(&opponents, 1.75).unwrap();
+        let expected: f64 = opponents.iter()
+            .map(|&opponent| 1.0 / (1.0 + 10f64.powf((opponent - performance) / 400.0)))
+            .sum();
+        assert!((expected - 1.75).abs() < 1e-9);
+        assert_eq!(Some(1500.0), rating_system.performance_rating(&[1500.0], 0.5));
+        assert_eq!(None, rating_system.performance_rating(&opponents, 0.0));
+        assert_eq!(None, rating_system.performance_rating(&opponents, 3.0));
+        assert_eq!(None, rating_system.performance_rating(&[], 0.0));
+        // A near perfect score is far above every opponent.
+        let performance = rating_system.performance_rating(&opponents, 2.999).unwrap();
+        assert!(performance > 2500.0);
+    }
+
+    #[test]
+    fn rating_period() {
+        let rating_system = EloRanking::<f64>::from_k_factor(32);
+        let mut players = vec![
+            Veteran { rating: 1600.0, games: 10, peak: 1600.0 },
+            Veteran { rating: 1500.0, games: 10, peak: 1500.0 },
+            Veteran { rating: 1400.0, games: 10, peak: 1400.0 },
+            Veteran { rating: 1450.0, games: 10, peak: 1450.0 },
+        ];
+        let results = vec![
+            MatchResult::new(1, 0, Outcome::Win),
+            MatchResult::new(1, 2, Outcome::Draw),
+            MatchResult::new(2, 0, Outcome::Partial(0.25)),
+        ];
+        let expected_one = rating_system.rating_change(&players[1], &players[0], 1.0).0 +
+            rating_system.rating_change(&players[1], &players[2], 0.5).0;
+        rating_system.rating_period(&mut players, &results).unwrap();
+        assert!((players[1].rating - 1500.0 - expected_one).abs() < 1e-9);
+        assert!((players.iter().map(|player| player.rating).sum::<f64>() -
+                 5950.0).abs() < 1e-9);
+        // Every player who played changes once, and every game counts.
+        assert_eq!(vec![12, 12, 12, 10], players.iter()
+                   .map(|player| player.games)
+                   .collect::<Vec<usize>>());
+
+        let ratings: Vec<f64> = players.iter().map(|player| player.rating).collect();
+        let results = vec![
+            MatchResult::new(0, 1, Outcome::Win),
+            MatchResult::new(0, 4, Outcome::Win),
+        ];
+        assert_eq!(Err(Error::UnknownPlayer(4)),
+                   rating_system.rating_period(&mut players, &results));
+        assert_eq!(ratings, players.iter()
+                   .map(|player| player.rating)
+                   .collect::<Vec<f64>>());
+    }
+
+    #[test]
+    fn advantage() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(32);
+        rating_system.set_advantage(50.0);
+        let home = Precise { rating: 1500.0 };
+        let away = Precise { rating: 1550.0 };
+        assert_eq!(0.5, rating_system
+                   .predict_with_advantage(&home, &away, Some(Side::PlayerOne)).win);
+        assert_eq!((0.0, 0.0), rating_system.rating_change_with_advantage(
+            &home, &away, 0.5, Some(Side::PlayerOne)));
+        assert_eq!(rating_system.rating_change(&Precise { rating: 1500.0 },
+                                               &Precise { rating: 1600.0 }, 1.0),
+                   rating_system.rating_change_with_advantage(
+                       &home, &away, 1.0, Some(Side::PlayerTwo)));
+
+        // Games where the home side scores 64% between equal players.
+        let mut games = Vec::new();
+        for game in 0..100 {
+            let score = if game < 64 { 1.0 } else { 0.0 };
+            games.push((1500.0 + game as f64, 1500.0 + game as f64, score));
+        }
+        let advantage = rating_system.fit_advantage(&games);
+        assert!((advantage - rating_system.rating_difference(0.64)).abs() < 1e-6);
+        assert_eq!(f64::INFINITY, rating_system.fit_advantage(&[(1500.0, 1400.0, 1.0)]));
+        assert_eq!(0.0, rating_system.fit_advantage(&[]));
+    }
+
+    #[test]
+    fn margin_of_victory() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(20);
+        let favorite = Precise { rating: 1600.0 };
+        let underdog = Precise { rating: 1500.0 };
+        // Without a multiplier the margin is ignored.
+        assert_eq!(rating_system.rating_change(&favorite, &underdog, 1.0),
+                   rating_system.rating_change_with_margin(&favorite, &underdog,
+                                                           1.0, 14.0, None));
+        rating_system.set_margin_of_victory(NflMargin);
+        let plain = rating_system.rating_change(&favorite, &underdog, 1.0).0;
+        let (change, _) = rating_system.rating_change_with_margin(
+            &favorite, &underdog, 1.0, 14.0, None);
+        let multiplier = 15f64.ln() * 2.2 / (100.0 * 0.001 + 2.2);
+        assert!((change - plain * multiplier).abs() < 1e-9);
+        // An upset is scaled by the rating difference of the underdog.
+        let plain = rating_system.rating_change(&favorite, &underdog, 0.0).0;
+        let (change, _) = rating_system.rating_change_with_margin(
+            &favorite, &underdog, 0.0, 14.0, None);
+        let multiplier = 15f64.ln() * 2.2 / (-100.0 * 0.001 + 2.2);
+        assert!((change - plain * multiplier).abs() < 1e-9);
+        // The home advantage counts towards the rating difference.
+        rating_system.set_advantage(65.0);
+        let (change, _) = rating_system.rating_change_with_margin(
+            &favorite, &underdog, 1.0, 3.0, Some(Side::PlayerOne));
+        let (plain, _) = rating_system.rating_change_with_advantage(
+            &favorite, &underdog, 1.0, Some(Side::PlayerOne));
+        let multiplier = 4f64.ln() * 2.2 / (165.0 * 0.001 + 2.2);
+        assert!((change - plain * multiplier).abs() < 1e-9);
+
+        let mut players = vec![Precise { rating: 1500.0 }, Precise { rating: 1500.0 }];
+        let mut result = MatchResult::new(0, 1, Outcome::Draw);
+        result.margin = Some(0.0);
+        rating_system.apply(&mut players, &result).unwrap();
+        assert_eq!(1500.0, players[0].rating);
+        rating_system.set_margin_of_victory(NbaMargin);
+        let (first, second) = players.split_at_mut(1);
+        rating_system.win_by::<Precise>(&mut first[0], &mut second[0], 7.0);
+        assert!((players[0].rating - 1500.0 - 10.0 * 10f64.powf(0.8) / 7.5).abs()
+                < 1e-9);
+    }
+
+    #[test]
+    fn provisional_rating_period() {
+        let mut rating_system = EloRanking::<f64>::from_k_factor(32);
+        rating_system.set_provisional_games(10);
+        let players = || vec![
+            Veteran { rating: 1500.0, games: 0, peak: 1500.0 },
+            Veteran { rating: 1800.0, games: 50, peak: 1800.0 },
+            Veteran { rating: 1800.0, games: 50, peak: 1800.0 },
+            Veteran { rating: 1800.0, games: 50, peak: 1800.0 },
+        ];
+        let results: Vec<MatchResult<f64>> = (1..4)
+            .map(|opponent| MatchResult::new(0, opponent, Outcome::Draw))
+            .collect();
+        let mut in_period = players();
+        rating_system.rating_period(&mut in_period, &results).unwrap();
+        let mut in_turn = players();
+        for result in &results {
+            rating_system.apply(&mut in_turn, result).unwrap();
+        }
+        assert_eq!(1800.0, in_period[0].rating);
+        assert_eq!(in_turn[0].rating, in_period[0].rating);
+        assert_eq!(1800.0, in_period[1].rating);
+        // Every game of the period is counted.
+        assert_eq!(3, in_period[0].games);
+        assert_eq!(51, in_period[1].games);
+        in_period.push(Veteran { rating: 1000.0, games: 50, peak: 1000.0 });
+        rating_system.apply(&mut in_period, &MatchResult::new(0, 4, Outcome::Win))
+            .unwrap();
+        assert_eq!(1700.0, in_period[0].rating);
+
+        // (R × N + Σ opponents + 400 × (W - L)) / (N + m).
+        let mut players = players();
+        players[0].games = 2;
+        let results = vec![
+            MatchResult::new(0, 1, Outcome::Win),
+            MatchResult::new(2, 0, Outcome::Win),
+            MatchResult::new(0, 3, Outcome::Win),
+        ];
+        rating_system.rating_period(&mut players, &results).unwrap();
+        assert!((players[0].rating -
+                 (1500.0 * 2.0 + 5400.0 + 400.0) / 5.0).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,43 @@
+//! Dense linear algebra.
+
+use std::cmp::Ordering;
+
+/// Solve a linear system by Gaussian elimination, returning the solution and
+/// the determinant of the matrix.
+pub fn solve(mut matrix: Vec<Vec<f64>>, mut vector: Vec<f64>) -> (Vec<f64>, f64) {
+    let size = vector.len();
+    let mut determinant = 1.0;
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| {
+                matrix[a][column].abs()
+                    .partial_cmp(&matrix[b][column].abs())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap();
+        if pivot != column {
+            matrix.swap(pivot, column);
+            vector.swap(pivot, column);
+            determinant = -determinant;
+        }
+        determinant *= matrix[column][column];
+        for row in column + 1..size {
+            let factor = matrix[row][column] / matrix[column][column];
+            let pivot_row = matrix[column].clone();
+            for (value, pivot) in
+                matrix[row].iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot;
+            }
+            vector[row] -= factor * vector[column];
+        }
+    }
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let mut value = vector[row];
+        for x in row + 1..size {
+            value -= matrix[row][x] * solution[x];
+        }
+        solution[row] = value / matrix[row][row];
+    }
+    return (solution, determinant);
+}
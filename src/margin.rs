@@ -0,0 +1,63 @@
+//! Margin of victory multipliers of the K-factor.
+
+use float::Float;
+
+/// MarginOfVictory.
+///
+/// Scales the K-factor of a decisive game by how much the winner won by.
+/// The multiplier usually shrinks as the winner's rating lead grows, which
+/// corrects for the autocorrelation of favorites winning by more and would
+/// otherwise inflate their ratings.
+pub trait MarginOfVictory<F: Float> {
+    /// Returns the multiplier of the K-factor for a winning margin, in points,
+    /// goals or runs, and the rating difference of the winner over the loser.
+    fn multiplier(&self, margin: F, winner_difference: F) -> F;
+}
+
+/// NflMargin.
+///
+/// The multiplier of FiveThirtyEight's NFL Elo:
+/// ln(margin + 1) × 2.2 / (0.001 × winner difference + 2.2).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NflMargin;
+
+impl<F: Float> MarginOfVictory<F> for NflMargin {
+    fn multiplier(&self, margin: F, winner_difference: F) -> F {
+        let two_point_two = F::from_f64(2.2);
+        return (margin.abs() + F::from_f64(1.0)).ln() * two_point_two /
+            (F::from_f64(0.001) * winner_difference + two_point_two);
+    }
+}
+
+/// NbaMargin.
+///
+/// The multiplier of FiveThirtyEight's NBA Elo:
+/// (margin + 3)^0.8 / (7.5 + 0.006 × winner difference).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NbaMargin;
+
+impl<F: Float> MarginOfVictory<F> for NbaMargin {
+    fn multiplier(&self, margin: F, winner_difference: F) -> F {
+        return (margin.abs() + F::from_f64(3.0)).powf(F::from_f64(0.8)) /
+            (F::from_f64(7.5) + F::from_f64(0.006) * winner_difference);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipliers() {
+        // A 7 point win between equal teams.
+        assert!((NflMargin.multiplier(7.0, 0.0) - 8f64.ln()).abs() < 1e-12);
+        assert!((NbaMargin.multiplier(7.0, 0.0) - 10f64.powf(0.8) / 7.5).abs()
+                < 1e-12);
+        // Favorites gain less for the same margin, underdogs more.
+        for model in &[&NflMargin as &dyn MarginOfVictory<f64>, &NbaMargin] {
+            assert!(model.multiplier(10.0, 200.0) < model.multiplier(10.0, 0.0));
+            assert!(model.multiplier(10.0, -200.0) > model.multiplier(10.0, 0.0));
+            assert!(model.multiplier(20.0, 0.0) > model.multiplier(10.0, 0.0));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Functions of the standard normal distribution.
+
+use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+/// Complementary error function, with a fractional error below 1.2e-7.
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + z / 2.0);
+    let r = t * (-z * z - 1.26551223 + t * (1.00002368 + t * (
+        0.37409196 + t * (0.09678418 + t * (-0.18628806 + t * (
+            0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (
+                -0.82215223 + t * 0.17087277
+            )))
+        )))
+    ))).exp();
+    return if x < 0.0 { 2.0 - r } else { r };
+}
+
+/// Inverse of the complementary error function.
+pub fn inverse_erfc(y: f64) -> f64 {
+    if y >= 2.0 {
+        return -100.0;
+    } else if y <= 0.0 {
+        return 100.0;
+    }
+    let lower = y < 1.0;
+    let y = if lower { y } else { 2.0 - y };
+    let t = (-2.0 * (y / 2.0).ln()).sqrt();
+    let mut x = -FRAC_1_SQRT_2 * ((2.30753 + t * 0.27061) /
+                                   (1.0 + t * (0.99229 + t * 0.04481)) - t);
+    for _ in 0..2 {
+        let error = erfc(x) - y;
+        x += error / (2.0 / PI.sqrt() * (-x * x).exp() - x * error);
+    }
+    return if lower { x } else { -x };
+}
+
+/// Probability density function.
+pub fn pdf(x: f64) -> f64 {
+    return (-x * x / 2.0).exp() / (2.0 * PI).sqrt();
+}
+
+/// Cumulative distribution function.
+pub fn cdf(x: f64) -> f64 {
+    return 0.5 * erfc(-x / SQRT_2);
+}
+
+/// Percent point function, the inverse of `cdf`.
+pub fn ppf(p: f64) -> f64 {
+    return -SQRT_2 * inverse_erfc(2.0 * p);
+}
+
+/// Additive correction of the mean of a difference truncated to a win.
+pub fn v_win(difference: f64, draw_margin: f64) -> f64 {
+    let x = difference - draw_margin;
+    let denominator = cdf(x);
+    return if denominator > 0.0 { pdf(x) / denominator } else { -x };
+}
+
+/// Multiplicative correction of the variance of a difference truncated to
+/// a win.
+pub fn w_win(difference: f64, draw_margin: f64) -> f64 {
+    if cdf(difference - draw_margin) <= 0.0 {
+        return if difference < 0.0 { 1.0 } else { 0.0 };
+    }
+    let v = v_win(difference, draw_margin);
+    return v * (v + difference - draw_margin);
+}
+
+/// Additive correction of the mean of a difference truncated to a draw.
+pub fn v_draw(difference: f64, draw_margin: f64) -> f64 {
+    let a = draw_margin - difference.abs();
+    let b = -draw_margin - difference.abs();
+    let denominator = cdf(a) - cdf(b);
+    let v = if denominator > 0.0 {
+        (pdf(b) - pdf(a)) / denominator
+    } else {
+        a
+    };
+    return if difference < 0.0 { -v } else { v };
+}
+
+/// Multiplicative correction of the variance of a difference truncated to
+/// a draw.
+pub fn w_draw(difference: f64, draw_margin: f64) -> f64 {
+    let a = draw_margin - difference.abs();
+    let b = -draw_margin - difference.abs();
+    let denominator = cdf(a) - cdf(b);
+    if denominator <= 0.0 {
+        return 1.0;
+    }
+    let v = v_draw(difference.abs(), draw_margin);
+    return v * v + (a * pdf(a) - b * pdf(b)) / denominator;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution() {
+        assert!((cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((cdf(1.96) - 0.9750021).abs() < 1e-6);
+        assert!((pdf(0.0) - 0.3989423).abs() < 1e-7);
+        assert!((ppf(0.975) - 1.959964).abs() < 1e-5);
+        assert!((ppf(cdf(-0.7)) + 0.7).abs() < 1e-6);
+    }
+}
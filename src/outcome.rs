@@ -0,0 +1,220 @@
+//! Typed results of games.
+
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+
+use float::Float;
+
+/// An error in a game result.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// A score outside of [0, 1].
+    InvalidScore(f64),
+    /// A player index outside of the players.
+    UnknownPlayer(usize),
+    /// A game of a player against themselves.
+    SamePlayer(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match *self {
+            Error::InvalidScore(score) => {
+                write!(f, "score {} is outside of [0, 1]", score)
+            },
+            Error::UnknownPlayer(player) => write!(f, "unknown player {}", player),
+            Error::SamePlayer(player) => {
+                write!(f, "player {} cannot play against themselves", player)
+            },
+        };
+    }
+}
+
+impl error::Error for Error {}
+
+/// The outcome of a game for the first player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome<F> {
+    /// The first player won.
+    Win,
+    /// The game was drawn.
+    Draw,
+    /// The first player lost.
+    Loss,
+    /// A partial score of the first player between 0 and 1, such as the
+    /// result of a match of several games.
+    Partial(F),
+}
+
+impl<F: Float> Outcome<F> {
+    /// Create an outcome from a score, checking that it is in [0, 1].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Outcome;
+    /// assert_eq!(Ok(Outcome::Win), Outcome::from_score(1.0));
+    /// assert_eq!(Ok(Outcome::Partial(0.25)), Outcome::from_score(0.25));
+    /// assert!(Outcome::from_score(1.5).is_err());
+    /// ```
+    pub fn from_score(score: F) -> Result<Outcome<F>, Error> {
+        let outcome = if score == F::from_f64(1.0) {
+            Outcome::Win
+        } else if score == F::from_f64(0.5) {
+            Outcome::Draw
+        } else if score == F::from_f64(0.0) {
+            Outcome::Loss
+        } else {
+            Outcome::Partial(score)
+        };
+        outcome.score()?;
+        return Ok(outcome);
+    }
+
+    /// Returns the score of the first player, or an error if a partial score
+    /// is outside of [0, 1].
+    pub fn score(&self) -> Result<F, Error> {
+        return match *self {
+            Outcome::Win => Ok(F::from_f64(1.0)),
+            Outcome::Draw => Ok(F::from_f64(0.5)),
+            Outcome::Loss => Ok(F::from_f64(0.0)),
+            Outcome::Partial(score) => {
+                if score >= F::from_f64(0.0) && score <= F::from_f64(1.0) {
+                    Ok(score)
+                } else {
+                    Err(Error::InvalidScore(score.to_f64()))
+                }
+            },
+        };
+    }
+
+    /// Returns the outcome for the second player.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Outcome;
+    /// assert_eq!(Outcome::Loss, Outcome::<f32>::Win.reverse());
+    /// assert_eq!(Outcome::Partial(0.75), Outcome::Partial(0.25).reverse());
+    /// ```
+    pub fn reverse(&self) -> Outcome<F> {
+        return match *self {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+            Outcome::Partial(score) => Outcome::Partial(F::from_f64(1.0) - score),
+        };
+    }
+}
+
+/// One of the two players of a game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    /// The first player.
+    PlayerOne,
+    /// The second player.
+    PlayerTwo,
+}
+
+/// The result of a game between two players, identified by their index.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult<F> {
+    /// The first player.
+    pub player_one: usize,
+    /// The second player.
+    pub player_two: usize,
+    /// The outcome for the first player.
+    pub outcome: Outcome<F>,
+    /// The player with the advantage of playing at home or moving first, if
+    /// any.
+    pub advantage: Option<Side>,
+    /// The winning margin in points, goals or runs, if known.
+    pub margin: Option<F>,
+    /// When the game was played, in any unit.
+    pub timestamp: Option<i64>,
+    /// Any other information about the game.
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl<F: Float> MatchResult<F> {
+    /// Create a result without an advantage, margin, timestamp or metadata.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::{MatchResult, Outcome};
+    /// let mut result = MatchResult::new(0, 1, Outcome::<f32>::Win);
+    /// result.timestamp = Some(1_500_000_000);
+    /// result.metadata.insert("event".to_string(), "Open".to_string());
+    /// ```
+    pub fn new(player_one: usize,
+               player_two: usize,
+               outcome: Outcome<F>) -> MatchResult<F> {
+        return MatchResult {
+            player_one,
+            player_two,
+            outcome,
+            advantage: None,
+            margin: None,
+            timestamp: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Check that the result is valid for a number of players.
+    pub fn validate(&self, players: usize) -> Result<(), Error> {
+        for &player in &[self.player_one, self.player_two] {
+            if player >= players {
+                return Err(Error::UnknownPlayer(player));
+            }
+        }
+        if self.player_one == self.player_two {
+            return Err(Error::SamePlayer(self.player_one));
+        }
+        self.outcome.score()?;
+        return Ok(());
+    }
+}
+
+/// Mutable references to two different players.
+pub fn pair<T>(players: &mut [T], one: usize, two: usize) -> (&mut T, &mut T) {
+    if one < two {
+        let (left, right) = players.split_at_mut(two);
+        return (&mut left[one], &mut right[0]);
+    }
+    let (left, right) = players.split_at_mut(one);
+    return (&mut right[0], &mut left[two]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validation() {
+        assert_eq!(Ok(0.5), Outcome::<f64>::Draw.score());
+        assert_eq!(Err(Error::InvalidScore(-0.5)), Outcome::Partial(-0.5).score());
+        assert!(Outcome::Partial(f64::NAN).score().is_err());
+        assert_eq!(Ok(Outcome::Loss), Outcome::from_score(0.0f64));
+
+        assert_eq!(Ok(()), MatchResult::new(0, 1, Outcome::<f64>::Win).validate(2));
+        assert_eq!(Err(Error::UnknownPlayer(2)),
+                   MatchResult::new(0, 2, Outcome::<f64>::Win).validate(2));
+        assert_eq!(Err(Error::SamePlayer(1)),
+                   MatchResult::new(1, 1, Outcome::<f64>::Win).validate(2));
+        assert_eq!(Err(Error::InvalidScore(2.0)),
+                   MatchResult::new(0, 1, Outcome::Partial(2.0)).validate(2));
+        assert_eq!("score 2 is outside of [0, 1]",
+                   Error::InvalidScore(2.0).to_string());
+    }
+
+    #[test]
+    fn pairs() {
+        let mut players = [0, 1, 2, 3];
+        let (a, b) = pair(&mut players, 3, 1);
+        assert_eq!((3, 1), (*a, *b));
+        let (a, b) = pair(&mut players, 0, 2);
+        assert_eq!((0, 2), (*a, *b));
+    }
+}
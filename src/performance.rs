@@ -0,0 +1,116 @@
+//! Tournament performance ratings.
+//!
+//! The performance rating of a player over some games is the rating at which
+//! their score would have been expected. Both methods here take the ratings
+//! of the opponents and the total score. `EloRanking::performance_rating`
+//! gives the exact inverse of the expectation curve instead.
+
+/// The rating differences of the FIDE table for scores from 0.50 to 1.00.
+const FIDE_DIFFERENCES: [f64; 51] = [
+    0.0, 7.0, 14.0, 21.0, 29.0, 36.0, 43.0, 50.0, 57.0, 65.0,
+    72.0, 80.0, 87.0, 95.0, 102.0, 110.0, 117.0, 125.0, 133.0, 141.0,
+    149.0, 158.0, 166.0, 175.0, 184.0, 193.0, 202.0, 211.0, 220.0, 230.0,
+    240.0, 251.0, 262.0, 273.0, 284.0, 296.0, 309.0, 322.0, 336.0, 351.0,
+    366.0, 383.0, 401.0, 422.0, 444.0, 470.0, 501.0, 538.0, 589.0, 677.0,
+    800.0,
+];
+
+/// Returns the rating difference of the FIDE table for a fractional score,
+/// rounded to two decimals.
+///
+/// # Example
+///
+/// ```
+/// # use elo::performance::fide_difference;
+/// assert_eq!(193.0, fide_difference(0.75));
+/// assert_eq!(-193.0, fide_difference(0.25));
+/// assert_eq!(800.0, fide_difference(1.0));
+/// ```
+pub fn fide_difference(fraction: f64) -> f64 {
+    let hundredths = (fraction.clamp(0.0, 1.0) * 100.0).round() as usize;
+    if hundredths >= 50 {
+        return FIDE_DIFFERENCES[hundredths - 50];
+    }
+    return -FIDE_DIFFERENCES[50 - hundredths];
+}
+
+/// Returns the mean of the ratings.
+fn mean(ratings: &[f64]) -> f64 {
+    return ratings.iter().sum::<f64>() / ratings.len() as f64;
+}
+
+/// The performance rating of the FIDE rating regulations: the average
+/// rating of the opponents plus the rating difference of the FIDE table for
+/// the fractional score.
+///
+/// A perfect score is 800 points above the average of the opponents, and a
+/// zero score 800 points below. Returns `None` without games.
+///
+/// # Example
+///
+/// ```
+/// # use elo::performance::fide;
+/// let opponents = [2400.0, 2500.0, 2600.0, 2500.0];
+/// assert_eq!(Some(2693.0), fide(&opponents, 3.0));
+/// ```
+pub fn fide(opponent_ratings: &[f64], score: f64) -> Option<f64> {
+    if opponent_ratings.is_empty() {
+        return None;
+    }
+    let fraction = score / opponent_ratings.len() as f64;
+    return Some(mean(opponent_ratings) + fide_difference(fraction));
+}
+
+/// The linear "algorithm of 400": the average rating of the opponents plus
+/// 400 points for every win and minus 400 for every loss, per game.
+///
+/// A perfect score is 400 points above the average of the opponents, and a
+/// zero score 400 points below. Returns `None` without games.
+///
+/// # Example
+///
+/// ```
+/// # use elo::performance::linear;
+/// let opponents = [2400.0, 2500.0, 2600.0, 2500.0];
+/// assert_eq!(Some(2700.0), linear(&opponents, 3.0));
+/// ```
+pub fn linear(opponent_ratings: &[f64], score: f64) -> Option<f64> {
+    if opponent_ratings.is_empty() {
+        return None;
+    }
+    let games = opponent_ratings.len() as f64;
+    let wins_minus_losses = 2.0 * score - games;
+    return Some(mean(opponent_ratings) + 400.0 * wins_minus_losses / games);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fide_table() {
+        assert_eq!(0.0, fide_difference(0.5));
+        assert_eq!(7.0, fide_difference(0.51));
+        assert_eq!(366.0, fide_difference(0.9));
+        assert_eq!(-366.0, fide_difference(0.1));
+        assert_eq!(-800.0, fide_difference(0.0));
+        // Rounded to two decimals.
+        assert_eq!(fide_difference(2.0 / 3.0), fide_difference(0.67));
+        for hundredths in 50..100 {
+            assert!(fide_difference(hundredths as f64 / 100.0) <
+                    fide_difference((hundredths + 1) as f64 / 100.0));
+        }
+    }
+
+    #[test]
+    fn performances() {
+        let opponents = [1800.0, 2000.0];
+        assert_eq!(Some(2700.0), fide(&opponents, 2.0));
+        assert_eq!(Some(1100.0), fide(&opponents, 0.0));
+        assert_eq!(Some(1900.0), fide(&opponents, 1.0));
+        assert_eq!(Some(2300.0), linear(&opponents, 2.0));
+        assert_eq!(Some(1700.0), linear(&opponents, 0.5));
+        assert_eq!(None, fide(&[], 0.0));
+        assert_eq!(None, linear(&[], 0.0));
+    }
+}
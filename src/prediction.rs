@@ -0,0 +1,364 @@
+//! Previews of a game: win, draw and loss probabilities, and the rating
+//! changes at stake.
+
+use float::Float;
+
+/// The probabilities of the outcomes of a game for the first player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Prediction<F> {
+    /// The probability of a win.
+    pub win: F,
+    /// The probability of a draw.
+    pub draw: F,
+    /// The probability of a loss.
+    pub loss: F,
+}
+
+impl<F: Float> Prediction<F> {
+    /// Returns the expected score, counting a draw as half a win.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::Prediction;
+    /// let prediction = Prediction { win: 0.5, draw: 0.3, loss: 0.2 };
+    /// assert_eq!(0.65, prediction.expected_score());
+    /// ```
+    pub fn expected_score(&self) -> F {
+        return self.win + self.draw / F::from_f64(2.0);
+    }
+}
+
+/// The rating changes of both players for every outcome of a game, from the
+/// point of view of the first player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stakes<F> {
+    /// The changes of the first and second player if the first one wins.
+    pub win: (F, F),
+    /// The changes of the first and second player in a tie.
+    pub tie: (F, F),
+    /// The changes of the first and second player if the first one loses.
+    pub loss: (F, F),
+}
+
+/// How likely draws are between two players.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawModel<F> {
+    /// Draws are not modelled: the expected score is the probability of a
+    /// win.
+    NoDraws,
+    /// Davidson's model, where the probability of a draw is proportional to
+    /// the geometric mean of the strengths of both players. The parameter
+    /// nu is the ratio of draws to wins between equal players.
+    Davidson(F),
+    /// Davidson's model with a draw parameter that depends on the level of
+    /// the game: ln nu = intercept + slope × the mean rating of both
+    /// players, so a positive slope makes draws more likely between strong
+    /// players. The parameters are the intercept and the slope.
+    DavidsonByLevel(F, F),
+    /// Rao and Kupper's model, where a player only wins if their performance
+    /// exceeds the opponent's by a threshold. The parameter theta is at
+    /// least 1, and 1 rules out draws.
+    RaoKupper(F),
+}
+
+impl<F: Float> DrawModel<F> {
+    /// Returns the outcome probabilities for a player with an expected score
+    /// `expected` without draws.
+    ///
+    /// The rating level of `DavidsonByLevel` is taken to be 0; see
+    /// `probabilities_at`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::DrawModel;
+    /// let prediction = DrawModel::Davidson(1.0f64).probabilities(0.5);
+    /// assert!((prediction.draw - 1.0 / 3.0).abs() < 1e-12);
+    /// ```
+    pub fn probabilities(&self, expected: F) -> Prediction<F> {
+        return self.probabilities_at(expected, F::from_f64(0.0));
+    }
+
+    /// Returns the outcome probabilities for a player with an expected score
+    /// `expected` without draws in a game whose players have a mean rating
+    /// of `level`. Only `DavidsonByLevel` depends on the level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::DrawModel;
+    /// let draw_model = DrawModel::DavidsonByLevel(-4.0f64, 0.002);
+    /// assert!(draw_model.probabilities_at(0.5, 2600.0).draw >
+    ///         draw_model.probabilities_at(0.5, 1600.0).draw);
+    /// ```
+    pub fn probabilities_at(&self, expected: F, level: F) -> Prediction<F> {
+        let one = F::from_f64(1.0);
+        // The strengths of the players, normalized to sum to 1.
+        let first = expected;
+        let second = one - expected;
+        let davidson = |nu: F| {
+            let tie = nu * (first * second).sqrt();
+            let total = first + second + tie;
+            return Prediction {
+                win: first / total,
+                draw: tie / total,
+                loss: second / total,
+            };
+        };
+        return match *self {
+            DrawModel::NoDraws => Prediction {
+                win: first,
+                draw: F::from_f64(0.0),
+                loss: second,
+            },
+            DrawModel::Davidson(nu) => davidson(nu),
+            DrawModel::DavidsonByLevel(intercept, slope) => {
+                davidson((intercept + slope * level).exp())
+            },
+            DrawModel::RaoKupper(theta) => {
+                let win = first / (first + theta * second);
+                let loss = second / (second + theta * first);
+                Prediction {
+                    win,
+                    draw: one - win - loss,
+                    loss,
+                }
+            },
+        };
+    }
+
+    /// Fit Davidson's draw parameter nu by maximum likelihood.
+    ///
+    /// Each game is given as the expected score of the first player without
+    /// draws and the actual score, where 0.5 is a draw. The fitted nu is 0
+    /// without draws and infinite if every game is a draw.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::DrawModel;
+    /// // Four wins, two draws and four losses between equal players.
+    /// let mut games = vec![(0.5, 1.0); 4];
+    /// games.extend(vec![(0.5, 0.5); 2]);
+    /// games.extend(vec![(0.5, 0.0); 4]);
+    /// match DrawModel::fit_davidson(&games) {
+    ///     DrawModel::Davidson(nu) => assert!((nu - 0.5f64).abs() < 1e-9),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn fit_davidson(games: &[(F, F)]) -> DrawModel<F> {
+        let half = F::from_f64(0.5);
+        let draws = games.iter().filter(|&&(_, score)| score == half).count();
+        // The geometric mean of the strengths in every game.
+        let means: Vec<f64> = games.iter()
+            .map(|&(expected, _)| {
+                let expected = expected.to_f64();
+                (expected * (1.0 - expected)).sqrt()
+            })
+            .filter(|&mean| mean > 0.0)
+            .collect();
+        if draws == 0 {
+            return DrawModel::Davidson(F::from_f64(0.0));
+        }
+        if draws >= means.len() {
+            return DrawModel::Davidson(F::from_f64(f64::INFINITY));
+        }
+        // The derivative of the log-likelihood times nu, which decreases
+        // from the number of draws to the number of draws minus games.
+        let slope = |nu: f64| {
+            return draws as f64 - means.iter()
+                .map(|&mean| nu * mean / (1.0 + nu * mean))
+                .sum::<f64>();
+        };
+        let mut lower = 0.0;
+        let mut upper = 1.0;
+        while slope(upper) > 0.0 {
+            lower = upper;
+            upper *= 2.0;
+        }
+        for _ in 0..100 {
+            let middle = (lower + upper) / 2.0;
+            if slope(middle) > 0.0 {
+                lower = middle;
+            } else {
+                upper = middle;
+            }
+        }
+        return DrawModel::Davidson(F::from_f64((lower + upper) / 2.0));
+    }
+
+    /// Fit `DavidsonByLevel` by maximum likelihood.
+    ///
+    /// Each game is given as the expected score of the first player without
+    /// draws, the mean rating of both players and the actual score, where
+    /// 0.5 is a draw. If every game has the same level the slope is 0, and
+    /// without draws, or with only draws, the intercept is infinite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::DrawModel;
+    /// // Strong players draw half of their games, weak players a fifth.
+    /// let mut games = Vec::new();
+    /// for &(level, draws) in &[(1600.0, 2), (2600.0, 5)] {
+    ///     games.extend(vec![(0.5, level, 0.5); draws]);
+    ///     games.extend(vec![(0.5, level, 1.0); (10 - draws) / 2]);
+    ///     games.extend(vec![(0.5, level, 0.0); (10 - draws) / 2]);
+    /// }
+    /// match DrawModel::fit_davidson_by_level(&games) {
+    ///     DrawModel::DavidsonByLevel(_, slope) => assert!(slope > 0.0f64),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn fit_davidson_by_level(games: &[(F, F, F)]) -> DrawModel<F> {
+        let half = F::from_f64(0.5);
+        // The geometric mean of the strengths, the level and whether the
+        // game was drawn, for every game that can be drawn.
+        let games: Vec<(f64, f64, bool)> = games.iter()
+            .map(|&(expected, level, score)| {
+                let expected = expected.to_f64();
+                ((expected * (1.0 - expected)).sqrt(), level.to_f64(), score == half)
+            })
+            .filter(|&(mean, _, _)| mean > 0.0)
+            .collect();
+        let draws = games.iter().filter(|&&(_, _, draw)| draw).count();
+        if draws == 0 || draws == games.len() {
+            let intercept = if draws == 0 { f64::NEG_INFINITY } else { f64::INFINITY };
+            return DrawModel::DavidsonByLevel(F::from_f64(intercept), F::from_f64(0.0));
+        }
+        // Levels are centered for the iteration and the intercept is moved
+        // back at the end.
+        let center = games.iter().map(|&(_, level, _)| level).sum::<f64>() /
+            games.len() as f64;
+        let spread = games.iter().any(|&(_, level, _)| level != center);
+        let (mut intercept, mut slope) = (0.0, 0.0);
+        // Newton's method on the concave log-likelihood
+        // Σ draws ln nu - Σ ln(1 + nu × mean).
+        for _ in 0..100 {
+            let (mut gradient, mut hessian) = ([0.0; 2], [[0.0; 2]; 2]);
+            for &(mean, level, draw) in &games {
+                let x = level - center;
+                let u = (intercept + slope * x).exp() * mean;
+                let q = u / (1.0 + u);
+                let residual = if draw { 1.0 - q } else { -q };
+                gradient[0] += residual;
+                gradient[1] += residual * x;
+                let weight = q * (1.0 - q);
+                hessian[0][0] += weight;
+                hessian[0][1] += weight * x;
+                hessian[1][1] += weight * x * x;
+            }
+            let (step_intercept, step_slope) = if spread {
+                let determinant = hessian[0][0] * hessian[1][1] -
+                    hessian[0][1] * hessian[0][1];
+                ((hessian[1][1] * gradient[0] - hessian[0][1] * gradient[1]) /
+                     determinant,
+                 (hessian[0][0] * gradient[1] - hessian[0][1] * gradient[0]) /
+                     determinant)
+            } else {
+                (gradient[0] / hessian[0][0], 0.0)
+            };
+            if !step_intercept.is_finite() || !step_slope.is_finite() {
+                break;
+            }
+            intercept += step_intercept;
+            slope += step_slope;
+            if step_intercept.abs() < 1e-12 && step_slope.abs() < 1e-15 {
+                break;
+            }
+        }
+        return DrawModel::DavidsonByLevel(F::from_f64(intercept - slope * center),
+                                          F::from_f64(slope));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probabilities() {
+        let models = [
+            DrawModel::NoDraws,
+            DrawModel::Davidson(0.8),
+            DrawModel::RaoKupper(1.5),
+        ];
+        for model in models.iter() {
+            for &expected in &[0.0, 0.1, 0.5, 0.75, 1.0] {
+                let prediction = model.probabilities(expected);
+                assert!((prediction.win + prediction.draw + prediction.loss
+                         - 1.0).abs() < 1e-12);
+                let mirrored = model.probabilities(1.0 - expected);
+                assert!((prediction.win - mirrored.loss).abs() < 1e-12);
+            }
+        }
+        // Equal players.
+        let prediction = DrawModel::RaoKupper(1.5).probabilities(0.5);
+        assert!((prediction.win - 0.4).abs() < 1e-12);
+        assert!((prediction.draw - 0.2).abs() < 1e-12);
+        let prediction = DrawModel::Davidson(0.8).probabilities(0.5);
+        assert!((prediction.draw / prediction.win - 0.8).abs() < 1e-12);
+        assert_eq!(0.75, DrawModel::NoDraws.probabilities(0.75).win);
+    }
+
+    #[test]
+    fn fit_davidson() {
+        // Outcomes in proportion to the probabilities of nu = 0.8.
+        let mut games = Vec::new();
+        for &expected in &[0.2f64, 0.5, 0.9] {
+            let prediction = DrawModel::Davidson(0.8).probabilities(expected);
+            for &(probability, score) in &[(prediction.win, 1.0),
+                                           (prediction.draw, 0.5),
+                                           (prediction.loss, 0.0)] {
+                let count = (probability * 100000.0).round() as usize;
+                games.extend(vec![(expected, score); count]);
+            }
+        }
+        match DrawModel::fit_davidson(&games) {
+            DrawModel::Davidson(nu) => assert!((nu - 0.8).abs() < 1e-3),
+            _ => unreachable!(),
+        }
+        assert_eq!(DrawModel::Davidson(0.0),
+                   DrawModel::fit_davidson(&[(0.5, 1.0), (0.3, 0.0)]));
+        assert_eq!(DrawModel::Davidson(f64::INFINITY),
+                   DrawModel::fit_davidson(&[(0.5, 0.5), (0.3, 0.5)]));
+    }
+
+    #[test]
+    fn fit_davidson_by_level() {
+        // Outcomes in proportion to the probabilities of ln nu =
+        // -5 + 0.002 × level.
+        let model = DrawModel::DavidsonByLevel(-5.0, 0.002);
+        let mut games = Vec::new();
+        for &level in &[1400.0f64, 2000.0, 2600.0] {
+            for &expected in &[0.3f64, 0.5, 0.8] {
+                let prediction = model.probabilities_at(expected, level);
+                for &(probability, score) in &[(prediction.win, 1.0),
+                                               (prediction.draw, 0.5),
+                                               (prediction.loss, 0.0)] {
+                    let count = (probability * 100000.0).round() as usize;
+                    games.extend(vec![(expected, level, score); count]);
+                }
+            }
+        }
+        match DrawModel::fit_davidson_by_level(&games) {
+            DrawModel::DavidsonByLevel(intercept, slope) => {
+                assert!((intercept + 5.0).abs() < 1e-2);
+                assert!((slope - 0.002).abs() < 1e-5);
+            },
+            _ => unreachable!(),
+        }
+        // A single level gives a constant nu, as `fit_davidson`.
+        let games = [(0.5, 2000.0, 1.0), (0.5, 2000.0, 0.5), (0.5, 2000.0, 0.0)];
+        match DrawModel::fit_davidson_by_level(&games) {
+            DrawModel::DavidsonByLevel(intercept, slope) => {
+                assert_eq!(0.0, slope);
+                assert!((intercept.exp() - 1.0).abs() < 1e-9);
+            },
+            _ => unreachable!(),
+        }
+        assert_eq!(DrawModel::DavidsonByLevel(f64::NEG_INFINITY, 0.0),
+                   DrawModel::fit_davidson_by_level(&[(0.5, 2000.0, 1.0)]));
+    }
+}
@@ -0,0 +1,682 @@
+//! The TrueSkill rating system.
+//!
+//! TrueSkill models the skill of every player as a normal distribution with
+//! mean mu and standard deviation sigma. A match between any number of teams
+//! is rated by passing messages over a factor graph until the team
+//! performance differences agree with the finishing order.
+
+use linear::solve;
+use normal::{ppf, v_draw, v_win, w_draw, w_win};
+
+/// Message passing stops once no message changes by more than this.
+const DELTA: f64 = 0.0001;
+
+/// Upper bound on the number of message passing iterations.
+const ITERATIONS: usize = 10;
+
+/// TrueSkill.
+pub trait TrueSkill {
+    /// Get the mean skill.
+    fn get_mu(&self) -> f64;
+    /// Get the standard deviation of the skill.
+    fn get_sigma(&self) -> f64;
+    /// Set the mean skill and its standard deviation.
+    fn set_rating(&mut self, mu: f64, sigma: f64);
+}
+
+/// A normal distribution in terms of precision and precision adjusted mean.
+#[derive(Clone, Copy)]
+struct Gaussian {
+    pi: f64,
+    tau: f64,
+}
+
+impl Gaussian {
+    fn new(mu: f64, sigma: f64) -> Gaussian {
+        let pi = 1.0 / (sigma * sigma);
+        return Gaussian {
+            pi,
+            tau: pi * mu,
+        };
+    }
+
+    fn uniform() -> Gaussian {
+        return Gaussian {
+            pi: 0.0,
+            tau: 0.0,
+        };
+    }
+
+    fn mu(&self) -> f64 {
+        return if self.pi == 0.0 { 0.0 } else { self.tau / self.pi };
+    }
+
+    fn sigma(&self) -> f64 {
+        return (1.0 / self.pi).sqrt();
+    }
+
+    fn mul(&self, other: Gaussian) -> Gaussian {
+        return Gaussian {
+            pi: self.pi + other.pi,
+            tau: self.tau + other.tau,
+        };
+    }
+
+    fn div(&self, other: Gaussian) -> Gaussian {
+        return Gaussian {
+            pi: self.pi - other.pi,
+            tau: self.tau - other.tau,
+        };
+    }
+
+    /// How far another distribution is from this one.
+    fn delta(&self, other: Gaussian) -> f64 {
+        let pi_delta = (self.pi - other.pi).abs();
+        if pi_delta.is_infinite() {
+            return 0.0;
+        }
+        return (self.tau - other.tau).abs().max(pi_delta.sqrt());
+    }
+}
+
+/// The connection between a factor and a variable, holding the last message
+/// the factor sent.
+struct Edge {
+    variable: usize,
+    message: Gaussian,
+}
+
+impl Edge {
+    fn new(variable: usize) -> Edge {
+        return Edge {
+            variable,
+            message: Gaussian::uniform(),
+        };
+    }
+}
+
+/// The marginals of every variable in the factor graph.
+struct Graph {
+    values: Vec<Gaussian>,
+}
+
+impl Graph {
+    /// Replace the message of a factor and update the marginal.
+    fn update_message(&mut self, edge: &mut Edge, message: Gaussian) -> f64 {
+        let old = self.values[edge.variable];
+        let value = old.div(edge.message).mul(message);
+        edge.message = message;
+        self.values[edge.variable] = value;
+        return old.delta(value);
+    }
+
+    /// Set the marginal and derive the message of the factor from it.
+    fn update_value(&mut self, edge: &mut Edge, value: Gaussian) -> f64 {
+        let old = self.values[edge.variable];
+        edge.message = value.mul(edge.message).div(old);
+        self.values[edge.variable] = value;
+        return old.delta(value);
+    }
+
+    /// The marginal of a variable without the message of one factor.
+    fn cavity(&self, edge: &Edge) -> Gaussian {
+        return self.values[edge.variable].div(edge.message);
+    }
+}
+
+/// Skill prior, widened by the dynamic factor tau.
+struct PriorFactor {
+    edge: Edge,
+    value: Gaussian,
+}
+
+impl PriorFactor {
+    fn down(&mut self, graph: &mut Graph) -> f64 {
+        return graph.update_value(&mut self.edge, self.value);
+    }
+}
+
+/// Performance as a noisy sample of skill.
+struct LikelihoodFactor {
+    mean: Edge,
+    value: Edge,
+    variance: f64,
+}
+
+impl LikelihoodFactor {
+    fn message(&self, cavity: Gaussian) -> Gaussian {
+        let a = 1.0 / (1.0 + self.variance * cavity.pi);
+        return Gaussian {
+            pi: a * cavity.pi,
+            tau: a * cavity.tau,
+        };
+    }
+
+    fn down(&mut self, graph: &mut Graph) -> f64 {
+        let message = self.message(graph.cavity(&self.mean));
+        return graph.update_message(&mut self.value, message);
+    }
+
+    fn up(&mut self, graph: &mut Graph) -> f64 {
+        let message = self.message(graph.cavity(&self.value));
+        return graph.update_message(&mut self.mean, message);
+    }
+}
+
+/// A variable as a weighted sum of other variables.
+struct SumFactor {
+    sum: Edge,
+    terms: Vec<Edge>,
+    coefficients: Vec<f64>,
+}
+
+impl SumFactor {
+    fn message(cavities: &[Gaussian], coefficients: &[f64]) -> Gaussian {
+        let mut pi_inverse = 0.0;
+        let mut mu = 0.0;
+        for (cavity, coefficient) in cavities.iter().zip(coefficients) {
+            mu += coefficient * cavity.mu();
+            pi_inverse += coefficient * coefficient / cavity.pi;
+        }
+        let pi = 1.0 / pi_inverse;
+        return Gaussian {
+            pi,
+            tau: pi * mu,
+        };
+    }
+
+    fn down(&mut self, graph: &mut Graph) -> f64 {
+        let cavities: Vec<Gaussian> = self.terms.iter()
+            .map(|edge| graph.cavity(edge))
+            .collect();
+        let message = SumFactor::message(&cavities, &self.coefficients);
+        return graph.update_message(&mut self.sum, message);
+    }
+
+    fn up(&mut self, graph: &mut Graph, index: usize) -> f64 {
+        let coefficient = self.coefficients[index];
+        let mut cavities = Vec::with_capacity(self.terms.len());
+        let mut coefficients = Vec::with_capacity(self.terms.len());
+        for (x, (edge, &c)) in
+            self.terms.iter().zip(self.coefficients.iter()).enumerate() {
+            if x == index {
+                cavities.push(graph.cavity(&self.sum));
+                coefficients.push(1.0 / coefficient);
+            } else {
+                cavities.push(graph.cavity(edge));
+                coefficients.push(-c / coefficient);
+            }
+        }
+        let message = SumFactor::message(&cavities, &coefficients);
+        return graph.update_message(&mut self.terms[index], message);
+    }
+}
+
+/// A team performance difference truncated by the observed outcome.
+struct TruncateFactor {
+    edge: Edge,
+    draw: bool,
+    draw_margin: f64,
+}
+
+impl TruncateFactor {
+    fn up(&mut self, graph: &mut Graph) -> f64 {
+        let cavity = graph.cavity(&self.edge);
+        let sqrt_pi = cavity.pi.sqrt();
+        let difference = cavity.tau / sqrt_pi;
+        let draw_margin = self.draw_margin * sqrt_pi;
+        let (v, w) = if self.draw {
+            (v_draw(difference, draw_margin), w_draw(difference, draw_margin))
+        } else {
+            (v_win(difference, draw_margin), w_win(difference, draw_margin))
+        };
+        let denominator = 1.0 - w;
+        let value = Gaussian {
+            pi: cavity.pi / denominator,
+            tau: (cavity.tau + sqrt_pi * v) / denominator,
+        };
+        return graph.update_value(&mut self.edge, value);
+    }
+}
+
+/// TrueSkillRanking.
+pub struct TrueSkillRanking {
+    beta: f64,
+    tau: f64,
+    draw_probability: f64,
+}
+
+impl Default for TrueSkillRanking {
+    /// The parameters for players starting at mu 25 and sigma 25/3.
+    fn default() -> TrueSkillRanking {
+        return TrueSkillRanking::new(25.0 / 6.0, 25.0 / 300.0, 0.10);
+    }
+}
+
+impl TrueSkillRanking {
+    /// Create a new TrueSkill ranking system.
+    ///
+    /// Beta is the distance in skill which guarantees about a 76% chance of
+    /// winning, tau is the dynamic factor added to every sigma before a
+    /// match and the draw probability sets the draw margin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::trueskill::TrueSkillRanking;
+    /// let trueskill_ranking = TrueSkillRanking::new(25.0 / 6.0, 25.0 / 300.0, 0.10);
+    /// ```
+    pub fn new(beta: f64, tau: f64, draw_probability: f64) -> TrueSkillRanking {
+        return TrueSkillRanking {
+            beta,
+            tau,
+            draw_probability,
+        }
+    }
+
+    /// Change beta.
+    pub fn set_beta(&mut self, beta: f64) {
+        self.beta = beta;
+    }
+
+    /// Returns beta.
+    pub fn get_beta(&self) -> f64 {
+        return self.beta;
+    }
+
+    /// Change the dynamic factor tau.
+    pub fn set_tau(&mut self, tau: f64) {
+        self.tau = tau;
+    }
+
+    /// Returns the dynamic factor tau.
+    pub fn get_tau(&self) -> f64 {
+        return self.tau;
+    }
+
+    /// Change the draw probability.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::trueskill::TrueSkillRanking;
+    /// # let mut trueskill_ranking = TrueSkillRanking::default();
+    /// trueskill_ranking.set_draw_probability(0.0);
+    /// assert_eq!(0.0, trueskill_ranking.get_draw_probability());
+    /// ```
+    pub fn set_draw_probability(&mut self, draw_probability: f64) {
+        self.draw_probability = draw_probability;
+    }
+
+    /// Returns the draw probability.
+    pub fn get_draw_probability(&self) -> f64 {
+        return self.draw_probability;
+    }
+
+    /// Internal method for the draw margin of a match with a number of
+    /// players.
+    fn draw_margin(&self, players: usize) -> f64 {
+        return ppf((self.draw_probability + 1.0) / 2.0) *
+            (players as f64).sqrt() * self.beta;
+    }
+
+    /// Rate a match between teams.
+    ///
+    /// Ranks give the finishing order of the teams, lower is better. Teams
+    /// with the same rank drew. Empty teams are skipped, and nothing changes
+    /// with fewer than two teams that have players.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one rank per team.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::trueskill::{TrueSkill, TrueSkillRanking};
+    /// # struct Player { mu: f64, sigma: f64 }
+    /// # impl TrueSkill for Player {
+    /// #     fn get_mu(&self) -> f64 { self.mu }
+    /// #     fn get_sigma(&self) -> f64 { self.sigma }
+    /// #     fn set_rating(&mut self, mu: f64, sigma: f64) {
+    /// #         self.mu = mu; self.sigma = sigma;
+    /// #     }
+    /// # }
+    /// # let new_player = || Player { mu: 25.0, sigma: 25.0 / 3.0 };
+    /// let (mut a, mut b, mut c) = (new_player(), new_player(), new_player());
+    /// let trueskill_ranking = TrueSkillRanking::default();
+    /// // Player a won, b and c drew for second place.
+    /// trueskill_ranking.rate(&mut [vec![&mut a], vec![&mut b], vec![&mut c]],
+    ///                        &[0, 1, 1]);
+    /// assert!(a.get_mu() > b.get_mu());
+    /// ```
+    pub fn rate<T: TrueSkill>(&self, teams: &mut [Vec<&mut T>], ranks: &[usize]) {
+        assert_eq!(teams.len(), ranks.len());
+        let mut order: Vec<usize> = (0..teams.len())
+            .filter(|&team| !teams[team].is_empty())
+            .collect();
+        if order.len() < 2 {
+            return;
+        }
+        order.sort_by_key(|&team| ranks[team]);
+
+        let mut priors = Vec::new();
+        let mut team_sizes = Vec::new();
+        for &team in &order {
+            for player in &teams[team] {
+                let sigma = player.get_sigma();
+                priors.push(Gaussian::new(
+                    player.get_mu(),
+                    (sigma * sigma + self.tau * self.tau).sqrt()
+                ));
+            }
+            team_sizes.push(teams[team].len());
+        }
+        let players = priors.len();
+        let team_count = team_sizes.len();
+        let perf_offset = players;
+        let team_perf_offset = 2 * players;
+        let team_diff_offset = 2 * players + team_count;
+        let mut graph = Graph {
+            values: vec![Gaussian::uniform(); team_diff_offset + team_count - 1],
+        };
+
+        let mut rating_layer: Vec<PriorFactor> = priors.iter()
+            .enumerate()
+            .map(|(x, &value)| PriorFactor { edge: Edge::new(x), value })
+            .collect();
+        let mut perf_layer: Vec<LikelihoodFactor> = (0..players)
+            .map(|x| LikelihoodFactor {
+                mean: Edge::new(x),
+                value: Edge::new(perf_offset + x),
+                variance: self.beta * self.beta,
+            })
+            .collect();
+        let mut start = 0;
+        let mut team_perf_layer = Vec::with_capacity(team_count);
+        for (team, &size) in team_sizes.iter().enumerate() {
+            team_perf_layer.push(SumFactor {
+                sum: Edge::new(team_perf_offset + team),
+                terms: (start..start + size)
+                    .map(|x| Edge::new(perf_offset + x))
+                    .collect(),
+                coefficients: vec![1.0; size],
+            });
+            start += size;
+        }
+        let mut team_diff_layer: Vec<SumFactor> = (0..team_count - 1)
+            .map(|team| SumFactor {
+                sum: Edge::new(team_diff_offset + team),
+                terms: vec![Edge::new(team_perf_offset + team),
+                            Edge::new(team_perf_offset + team + 1)],
+                coefficients: vec![1.0, -1.0],
+            })
+            .collect();
+        let mut trunc_layer: Vec<TruncateFactor> = (0..team_count - 1)
+            .map(|team| TruncateFactor {
+                edge: Edge::new(team_diff_offset + team),
+                draw: ranks[order[team]] == ranks[order[team + 1]],
+                draw_margin: self.draw_margin(
+                    team_sizes[team] + team_sizes[team + 1]
+                ),
+            })
+            .collect();
+
+        for factor in rating_layer.iter_mut() {
+            factor.down(&mut graph);
+        }
+        for factor in perf_layer.iter_mut() {
+            factor.down(&mut graph);
+        }
+        for factor in team_perf_layer.iter_mut() {
+            factor.down(&mut graph);
+        }
+        let last = team_count - 2;
+        for _ in 0..ITERATIONS {
+            let mut delta = 0.0f64;
+            if last == 0 {
+                team_diff_layer[0].down(&mut graph);
+                delta = trunc_layer[0].up(&mut graph);
+            } else {
+                for x in 0..last {
+                    team_diff_layer[x].down(&mut graph);
+                    delta = delta.max(trunc_layer[x].up(&mut graph));
+                    team_diff_layer[x].up(&mut graph, 1);
+                }
+                for x in (1..last + 1).rev() {
+                    team_diff_layer[x].down(&mut graph);
+                    delta = delta.max(trunc_layer[x].up(&mut graph));
+                    team_diff_layer[x].up(&mut graph, 0);
+                }
+            }
+            if delta <= DELTA {
+                break;
+            }
+        }
+        team_diff_layer[0].up(&mut graph, 0);
+        team_diff_layer[last].up(&mut graph, 1);
+        for factor in team_perf_layer.iter_mut() {
+            for x in 0..factor.terms.len() {
+                factor.up(&mut graph, x);
+            }
+        }
+        for factor in perf_layer.iter_mut() {
+            factor.up(&mut graph);
+        }
+
+        let mut x = 0;
+        for &team in &order {
+            for player in teams[team].iter_mut() {
+                let value = graph.values[x];
+                player.set_rating(value.mu(), value.sigma());
+                x += 1;
+            }
+        }
+    }
+
+    /// Returns the quality of a match between teams, the probability of a
+    /// draw relative to the most even match possible. Empty teams are
+    /// skipped, and fewer than two teams make a perfectly even match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::trueskill::{TrueSkill, TrueSkillRanking};
+    /// # struct Player { mu: f64, sigma: f64 }
+    /// # impl TrueSkill for Player {
+    /// #     fn get_mu(&self) -> f64 { self.mu }
+    /// #     fn get_sigma(&self) -> f64 { self.sigma }
+    /// #     fn set_rating(&mut self, mu: f64, sigma: f64) {
+    /// #         self.mu = mu; self.sigma = sigma;
+    /// #     }
+    /// # }
+    /// let strong = Player { mu: 30.0, sigma: 1.0 };
+    /// let weak = Player { mu: 20.0, sigma: 1.0 };
+    /// let trueskill_ranking = TrueSkillRanking::default();
+    /// assert!(trueskill_ranking.quality(&[vec![&strong], vec![&weak]]) < 0.3);
+    /// ```
+    pub fn quality<T: TrueSkill>(&self, teams: &[Vec<&T>]) -> f64 {
+        let teams: Vec<&Vec<&T>> = teams.iter()
+            .filter(|team| !team.is_empty())
+            .collect();
+        if teams.len() < 2 {
+            return 1.0;
+        }
+        let players: Vec<&T> = teams.iter()
+            .flat_map(|team| team.iter().cloned())
+            .collect();
+        let size = teams.len() - 1;
+        // Rows of the team comparison matrix.
+        let mut comparison = vec![vec![0.0; players.len()]; size];
+        let mut start = 0;
+        for (row, pair) in comparison.iter_mut().zip(teams.windows(2)) {
+            let middle = start + pair[0].len();
+            let end = middle + pair[1].len();
+            for value in &mut row[start..middle] {
+                *value = 1.0;
+            }
+            for value in &mut row[middle..end] {
+                *value = -1.0;
+            }
+            start = middle;
+        }
+        let beta_squared = self.beta * self.beta;
+        let mut ata = vec![vec![0.0; size]; size];
+        let mut middle = vec![vec![0.0; size]; size];
+        for row in 0..size {
+            for column in 0..size {
+                for (x, player) in players.iter().enumerate() {
+                    let product = comparison[row][x] * comparison[column][x];
+                    let sigma = player.get_sigma();
+                    ata[row][column] += beta_squared * product;
+                    middle[row][column] += (beta_squared + sigma * sigma) *
+                        product;
+                }
+            }
+        }
+        let end: Vec<f64> = comparison.iter()
+            .map(|row| {
+                row.iter()
+                    .zip(players.iter())
+                    .map(|(a, player)| a * player.get_mu())
+                    .sum()
+            })
+            .collect();
+        let (solution, middle_determinant) = solve(middle, end.clone());
+        let (_, ata_determinant) = solve(ata, vec![0.0; size]);
+        let e_arg: f64 = -0.5 * end.iter()
+            .zip(solution.iter())
+            .map(|(a, b)| a * b)
+            .sum::<f64>();
+        return e_arg.exp() * (ata_determinant / middle_determinant).sqrt();
+    }
+
+    /// Internal method for matches between two players.
+    fn calculate_rating<T: TrueSkill>(&self,
+                                      player_one: &mut T,
+                                      player_two: &mut T,
+                                      ranks: &[usize]) {
+        self.rate(&mut [vec![player_one], vec![player_two]], ranks);
+    }
+
+    pub fn win<T: TrueSkill>(&self, winner: &mut T, loser: &mut T) {
+        self.calculate_rating(winner, loser, &[0, 1]);
+    }
+
+    pub fn tie<T: TrueSkill>(&self, player_one: &mut T, player_two: &mut T) {
+        self.calculate_rating(player_one, player_two, &[0, 0]);
+    }
+
+    pub fn loss<T: TrueSkill>(&self, loser: &mut T, winner: &mut T) {
+        self.win::<T>(winner, loser);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RatingObject {
+        mu: f64,
+        sigma: f64,
+    }
+
+    impl RatingObject {
+        pub fn new() -> RatingObject {
+            return RatingObject {
+                mu: 25.0,
+                sigma: 25.0 / 3.0,
+            };
+        }
+    }
+
+    impl TrueSkill for RatingObject {
+        fn get_mu(&self) -> f64 {
+            return self.mu;
+        }
+        fn get_sigma(&self) -> f64 {
+            return self.sigma;
+        }
+        fn set_rating(&mut self, mu: f64, sigma: f64) {
+            self.mu = mu;
+            self.sigma = sigma;
+        }
+    }
+
+    fn assert_rating(player: &RatingObject, mu: f64, sigma: f64) {
+        assert!((player.get_mu() - mu).abs() < 0.001,
+                "mu {} != {}", player.get_mu(), mu);
+        assert!((player.get_sigma() - sigma).abs() < 0.001,
+                "sigma {} != {}", player.get_sigma(), sigma);
+    }
+
+    #[test]
+    fn one_versus_one() {
+        let rating_system = TrueSkillRanking::default();
+        let mut player_one = RatingObject::new();
+        let mut player_two = RatingObject::new();
+        assert!((rating_system.quality(&[vec![&player_one], vec![&player_two]])
+                 - 0.447).abs() < 0.001);
+        assert_eq!(1.0, rating_system.quality::<RatingObject>(&[]));
+        assert_eq!(1.0, rating_system.quality(&[vec![&player_one]]));
+        rating_system.win::<RatingObject>(&mut player_one, &mut player_two);
+        assert_rating(&player_one, 29.396, 7.171);
+        assert_rating(&player_two, 20.604, 7.171);
+
+        let mut player_one = RatingObject::new();
+        let mut player_two = RatingObject::new();
+        rating_system.tie::<RatingObject>(&mut player_one, &mut player_two);
+        assert_rating(&player_one, 25.0, 6.458);
+        assert_rating(&player_two, 25.0, 6.458);
+    }
+
+    #[test]
+    fn two_versus_two() {
+        let rating_system = TrueSkillRanking::default();
+        let mut players: Vec<RatingObject> =
+            (0..4).map(|_| RatingObject::new()).collect();
+        {
+            let (one, two) = players.split_at_mut(2);
+            let (a, b) = one.split_at_mut(1);
+            let (c, d) = two.split_at_mut(1);
+            assert!((rating_system.quality(&[vec![&a[0], &b[0]],
+                                             vec![&c[0], &d[0]]])
+                     - 0.447).abs() < 0.001);
+            rating_system.rate(&mut [vec![&mut a[0], &mut b[0]],
+                                     vec![&mut c[0], &mut d[0]]],
+                               &[0, 1]);
+        }
+        assert_rating(&players[0], 28.108, 7.774);
+        assert_rating(&players[1], 28.108, 7.774);
+        assert_rating(&players[2], 21.892, 7.774);
+        assert_rating(&players[3], 21.892, 7.774);
+    }
+
+    #[test]
+    fn free_for_all() {
+        let rating_system = TrueSkillRanking::default();
+        let mut a = RatingObject::new();
+        let mut b = RatingObject::new();
+        let mut c = RatingObject::new();
+        assert!((rating_system.quality(&[vec![&a], vec![&b], vec![&c]])
+                 - 0.200).abs() < 0.001);
+        // Teams are given out of order to check that ranks are respected.
+        rating_system.rate(&mut [vec![&mut c], vec![&mut a], vec![&mut b]],
+                           &[2, 0, 1]);
+        assert_rating(&a, 31.675, 6.656);
+        assert_rating(&b, 25.0, 6.208);
+        assert_rating(&c, 18.325, 6.656);
+    }
+
+    #[test]
+    fn empty_teams() {
+        let rating_system = TrueSkillRanking::default();
+        let mut a = RatingObject::new();
+        let mut b = RatingObject::new();
+        assert!((rating_system.quality(&[vec![&a], vec![], vec![&b]])
+                 - 0.447).abs() < 0.001);
+        rating_system.rate(&mut [vec![&mut a], vec![], vec![&mut b]], &[0, 1, 2]);
+        assert_rating(&a, 29.396, 7.171);
+        assert_rating(&b, 20.604, 7.171);
+    }
+}
@@ -0,0 +1,410 @@
+//! The Weng-Lin Bayesian approximation rating systems, as used by OpenSkill.
+//!
+//! Like TrueSkill, every player has a mean skill mu and a standard deviation
+//! sigma, but matches are rated with closed form updates instead of message
+//! passing. Weng and Lin derive updates for several ranking models, which
+//! differ in how the outcome of a match is modelled.
+
+use normal::{v_draw, v_win, w_draw, w_win};
+
+/// Weng-Lin.
+pub trait WengLin {
+    /// Get the mean skill.
+    fn get_mu(&self) -> f64;
+    /// Get the standard deviation of the skill.
+    fn get_sigma(&self) -> f64;
+    /// Set the mean skill and its standard deviation.
+    fn set_rating(&mut self, mu: f64, sigma: f64);
+}
+
+/// The ranking model used to rate a match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Model {
+    /// Plackett-Luce, which models the full finishing order at once.
+    PlackettLuce,
+    /// Bradley-Terry, comparing every pair of teams.
+    BradleyTerryFull,
+    /// Bradley-Terry, comparing only teams adjacent in the finishing order.
+    BradleyTerryPart,
+    /// Thurstone-Mosteller, comparing every pair of teams.
+    ThurstoneMostellerFull,
+    /// Thurstone-Mosteller, comparing only teams adjacent in the finishing
+    /// order.
+    ThurstoneMostellerPart,
+}
+
+/// Sums of the ratings of a team.
+struct Team {
+    mu: f64,
+    sigma_squared: f64,
+    rank: usize,
+}
+
+/// WengLinRanking.
+pub struct WengLinRanking {
+    model: Model,
+    beta: f64,
+    kappa: f64,
+    tau: f64,
+    epsilon: f64,
+}
+
+impl WengLinRanking {
+    /// Create a new Weng-Lin ranking system.
+    ///
+    /// The remaining parameters start at the OpenSkill defaults for players
+    /// rated at mu 25 and sigma 25/3.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::weng_lin::{Model, WengLinRanking};
+    /// let weng_lin_ranking = WengLinRanking::new(Model::PlackettLuce);
+    /// ```
+    pub fn new(model: Model) -> WengLinRanking {
+        return WengLinRanking {
+            model,
+            beta: 25.0 / 6.0,
+            kappa: 0.0001,
+            tau: 25.0 / 300.0,
+            epsilon: 0.1,
+        }
+    }
+
+    /// Change the ranking model.
+    pub fn set_model(&mut self, model: Model) {
+        self.model = model;
+    }
+
+    /// Returns the ranking model.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::weng_lin::{Model, WengLinRanking};
+    /// # let weng_lin_ranking = WengLinRanking::new(Model::PlackettLuce);
+    /// assert_eq!(Model::PlackettLuce, weng_lin_ranking.get_model());
+    /// ```
+    pub fn get_model(&self) -> Model {
+        return self.model;
+    }
+
+    /// Change beta, the variance of the performance around the skill.
+    pub fn set_beta(&mut self, beta: f64) {
+        self.beta = beta;
+    }
+
+    /// Returns beta.
+    pub fn get_beta(&self) -> f64 {
+        return self.beta;
+    }
+
+    /// Change kappa, the smallest fraction of its variance a skill keeps
+    /// after a match.
+    pub fn set_kappa(&mut self, kappa: f64) {
+        self.kappa = kappa;
+    }
+
+    /// Returns kappa.
+    pub fn get_kappa(&self) -> f64 {
+        return self.kappa;
+    }
+
+    /// Change the dynamic factor tau, which is added to every sigma before a
+    /// match.
+    pub fn set_tau(&mut self, tau: f64) {
+        self.tau = tau;
+    }
+
+    /// Returns the dynamic factor tau.
+    pub fn get_tau(&self) -> f64 {
+        return self.tau;
+    }
+
+    /// Change the draw margin epsilon of the Thurstone-Mosteller models.
+    pub fn set_epsilon(&mut self, epsilon: f64) {
+        self.epsilon = epsilon;
+    }
+
+    /// Returns the draw margin epsilon.
+    pub fn get_epsilon(&self) -> f64 {
+        return self.epsilon;
+    }
+
+    /// Internal method for the contribution of comparing team i with team q
+    /// to the mean and variance updates of team i.
+    fn pairwise(&self, i: &Team, q: &Team) -> (f64, f64) {
+        let c = (i.sigma_squared + q.sigma_squared +
+                 2.0 * self.beta * self.beta).sqrt();
+        let sigma_squared_to_c = i.sigma_squared / c;
+        let gamma = i.sigma_squared.sqrt() / c;
+        match self.model {
+            Model::BradleyTerryFull | Model::BradleyTerryPart => {
+                let p = 1.0 / (1.0 + ((q.mu - i.mu) / c).exp());
+                let score = if q.rank > i.rank {
+                    1.0
+                } else if q.rank == i.rank {
+                    0.5
+                } else {
+                    0.0
+                };
+                return (sigma_squared_to_c * (score - p),
+                        gamma * sigma_squared_to_c / c * p * (1.0 - p));
+            },
+            _ => {
+                let t = (i.mu - q.mu) / c;
+                let margin = self.epsilon / c;
+                let (v, w) = if q.rank > i.rank {
+                    (v_win(t, margin), w_win(t, margin))
+                } else if q.rank < i.rank {
+                    (-v_win(-t, margin), w_win(-t, margin))
+                } else {
+                    (v_draw(t, margin), w_draw(t, margin))
+                };
+                return (sigma_squared_to_c * v,
+                        gamma * sigma_squared_to_c / c * w);
+            },
+        }
+    }
+
+    /// Internal method for the mean and variance updates of every team
+    /// under the Plackett-Luce model.
+    fn plackett_luce(&self, teams: &[Team]) -> Vec<(f64, f64)> {
+        let c = teams.iter()
+            .map(|team| team.sigma_squared + self.beta * self.beta)
+            .sum::<f64>()
+            .sqrt();
+        let strengths: Vec<f64> = teams.iter()
+            .map(|team| (team.mu / c).exp())
+            .collect();
+        let sums: Vec<f64> = teams.iter()
+            .map(|q| {
+                teams.iter()
+                    .zip(strengths.iter())
+                    .filter(|&(i, _)| i.rank >= q.rank)
+                    .map(|(_, strength)| strength)
+                    .sum()
+            })
+            .collect();
+        let ties: Vec<f64> = teams.iter()
+            .map(|q| teams.iter().filter(|i| i.rank == q.rank).count() as f64)
+            .collect();
+        return teams.iter()
+            .enumerate()
+            .map(|(x, i)| {
+                let mut omega = 0.0;
+                let mut delta = 0.0;
+                for (y, q) in teams.iter().enumerate() {
+                    if q.rank > i.rank {
+                        continue;
+                    }
+                    let p = strengths[x] / sums[y];
+                    delta += p * (1.0 - p) / ties[y];
+                    if x == y {
+                        omega += (1.0 - p) / ties[y];
+                    } else {
+                        omega -= p / ties[y];
+                    }
+                }
+                let gamma = i.sigma_squared.sqrt() / c;
+                return (omega * i.sigma_squared / c,
+                        gamma * delta * i.sigma_squared / (c * c));
+            })
+            .collect();
+    }
+
+    /// Rate a match between teams.
+    ///
+    /// Ranks give the finishing order of the teams, lower is better. Teams
+    /// with the same rank drew.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::weng_lin::{Model, WengLin, WengLinRanking};
+    /// # struct Player { mu: f64, sigma: f64 }
+    /// # impl WengLin for Player {
+    /// #     fn get_mu(&self) -> f64 { self.mu }
+    /// #     fn get_sigma(&self) -> f64 { self.sigma }
+    /// #     fn set_rating(&mut self, mu: f64, sigma: f64) {
+    /// #         self.mu = mu; self.sigma = sigma;
+    /// #     }
+    /// # }
+    /// # let new_player = || Player { mu: 25.0, sigma: 25.0 / 3.0 };
+    /// let (mut a, mut b, mut c) = (new_player(), new_player(), new_player());
+    /// let weng_lin_ranking = WengLinRanking::new(Model::BradleyTerryFull);
+    /// // Player a won, b and c drew for second place.
+    /// weng_lin_ranking.rate(&mut [vec![&mut a], vec![&mut b], vec![&mut c]],
+    ///                       &[0, 1, 1]);
+    /// assert!(a.get_mu() > b.get_mu());
+    /// ```
+    pub fn rate<T: WengLin>(&self, teams: &mut [Vec<&mut T>], ranks: &[usize]) {
+        assert_eq!(teams.len(), ranks.len());
+        if teams.len() < 2 {
+            return;
+        }
+        let tau_squared = self.tau * self.tau;
+        let mut order: Vec<usize> = (0..teams.len()).collect();
+        order.sort_by_key(|&team| ranks[team]);
+        let sorted: Vec<Team> = order.iter()
+            .map(|&team| Team {
+                mu: teams[team].iter().map(|player| player.get_mu()).sum(),
+                sigma_squared: teams[team].iter()
+                    .map(|player| {
+                        player.get_sigma() * player.get_sigma() + tau_squared
+                    })
+                    .sum(),
+                rank: ranks[team],
+            })
+            .collect();
+
+        let updates = match self.model {
+            Model::PlackettLuce => self.plackett_luce(&sorted),
+            Model::BradleyTerryFull | Model::ThurstoneMostellerFull => {
+                sorted.iter()
+                    .enumerate()
+                    .map(|(x, i)| {
+                        sorted.iter()
+                            .enumerate()
+                            .filter(|&(y, _)| x != y)
+                            .map(|(_, q)| self.pairwise(i, q))
+                            .fold((0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
+                    })
+                    .collect()
+            },
+            Model::BradleyTerryPart | Model::ThurstoneMostellerPart => {
+                sorted.iter()
+                    .enumerate()
+                    .map(|(x, i)| {
+                        sorted.iter()
+                            .enumerate()
+                            .filter(|&(y, _)| y + 1 == x || x + 1 == y)
+                            .map(|(_, q)| self.pairwise(i, q))
+                            .fold((0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
+                    })
+                    .collect()
+            },
+        };
+
+        for ((&team, sums), &(omega, delta)) in
+            order.iter().zip(sorted.iter()).zip(updates.iter()) {
+            for player in teams[team].iter_mut() {
+                let sigma_squared = player.get_sigma() * player.get_sigma() +
+                    tau_squared;
+                let share = sigma_squared / sums.sigma_squared;
+                let mu = player.get_mu() + share * omega;
+                let sigma = sigma_squared.sqrt() *
+                    (1.0 - share * delta).max(self.kappa).sqrt();
+                player.set_rating(mu, sigma);
+            }
+        }
+    }
+
+    /// Internal method for matches between two players.
+    fn calculate_rating<T: WengLin>(&self,
+                                    player_one: &mut T,
+                                    player_two: &mut T,
+                                    ranks: &[usize]) {
+        self.rate(&mut [vec![player_one], vec![player_two]], ranks);
+    }
+
+    pub fn win<T: WengLin>(&self, winner: &mut T, loser: &mut T) {
+        self.calculate_rating(winner, loser, &[0, 1]);
+    }
+
+    pub fn tie<T: WengLin>(&self, player_one: &mut T, player_two: &mut T) {
+        self.calculate_rating(player_one, player_two, &[0, 0]);
+    }
+
+    pub fn loss<T: WengLin>(&self, loser: &mut T, winner: &mut T) {
+        self.win::<T>(winner, loser);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RatingObject {
+        mu: f64,
+        sigma: f64,
+    }
+
+    impl RatingObject {
+        pub fn new() -> RatingObject {
+            return RatingObject {
+                mu: 25.0,
+                sigma: 25.0 / 3.0,
+            };
+        }
+    }
+
+    impl WengLin for RatingObject {
+        fn get_mu(&self) -> f64 {
+            return self.mu;
+        }
+        fn get_sigma(&self) -> f64 {
+            return self.sigma;
+        }
+        fn set_rating(&mut self, mu: f64, sigma: f64) {
+            self.mu = mu;
+            self.sigma = sigma;
+        }
+    }
+
+    const MODELS: [Model; 5] = [
+        Model::PlackettLuce,
+        Model::BradleyTerryFull,
+        Model::BradleyTerryPart,
+        Model::ThurstoneMostellerFull,
+        Model::ThurstoneMostellerPart,
+    ];
+
+    #[test]
+    fn reference_values() {
+        // The OpenSkill reference result for a one versus one match without
+        // the dynamic factor. Both Bradley-Terry models agree with
+        // Plackett-Luce for two teams.
+        for &model in &MODELS[..3] {
+            let mut rating_system = WengLinRanking::new(model);
+            rating_system.set_tau(0.0);
+            let mut player_one = RatingObject::new();
+            let mut player_two = RatingObject::new();
+            rating_system.win::<RatingObject>(&mut player_one, &mut player_two);
+            assert!((player_one.get_mu() - 27.63523138347365).abs() < 1e-9);
+            assert!((player_one.get_sigma() - 8.065506316323548).abs() < 1e-9);
+            assert!((player_two.get_mu() - 22.36476861652635).abs() < 1e-9);
+            assert!((player_two.get_sigma() - 8.065506316323548).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn ties() {
+        for &model in &MODELS {
+            let rating_system = WengLinRanking::new(model);
+            let mut player_one = RatingObject::new();
+            let mut player_two = RatingObject::new();
+            rating_system.tie::<RatingObject>(&mut player_one, &mut player_two);
+            assert!((player_one.get_mu() - 25.0).abs() < 1e-9);
+            assert!((player_two.get_mu() - 25.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn teams() {
+        for &model in &MODELS {
+            let rating_system = WengLinRanking::new(model);
+            let mut a = RatingObject::new();
+            let mut b = RatingObject::new();
+            b.sigma = 2.0;
+            let mut c = RatingObject::new();
+            let mut d = RatingObject::new();
+            rating_system.rate(&mut [vec![&mut c], vec![&mut a, &mut b],
+                                     vec![&mut d]],
+                               &[1, 0, 2]);
+            assert!(a.get_mu() > b.get_mu() && b.get_mu() > 25.0);
+            assert!(c.get_mu() > d.get_mu());
+            assert!(d.get_mu() < 25.0);
+        }
+    }
+}
@@ -0,0 +1,373 @@
+//! Whole-History Rating.
+//!
+//! Rémi Coulom's Whole-History Rating (2008) models the rating of every
+//! player as a Wiener process, so ratings may drift over time, and estimates
+//! the whole rating history of every player from all games at once. A game
+//! therefore informs the ratings a player had both before and after it.
+//!
+//! Ratings are on the Elo scale, where a difference of 400 points means odds
+//! of 10 to 1, and are centered on 0 by a prior of one virtual win and one
+//! virtual loss against a 0 rated player on every player's first day.
+
+use bradley_terry::{probability, SCALE};
+
+/// Added to the diagonal of the Hessian for numerical stability.
+const STABILITY: f64 = 0.001;
+
+/// An estimated rating with its uncertainty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    /// The rating.
+    pub rating: f64,
+    /// The standard deviation of the rating.
+    pub deviation: f64,
+}
+
+/// A time at which a player has played games.
+struct Day {
+    time: i64,
+    /// Rating in natural log-strength units.
+    rating: f64,
+    /// Posterior variance of the rating.
+    variance: f64,
+    /// Posterior covariance with the rating of the next day.
+    covariance: f64,
+    /// Opponent and score of every game on this day.
+    games: Vec<(usize, f64)>,
+}
+
+/// WholeHistoryRating.
+pub struct WholeHistoryRating {
+    w2: f64,
+    players: Vec<Vec<Day>>,
+}
+
+impl WholeHistoryRating {
+    /// Create a new Whole-History Rating engine.
+    ///
+    /// The variance of the Wiener process, w², is given in squared rating
+    /// points per unit of time. Coulom used 14 per day for Go.
+    ///
+    /// # Panics
+    ///
+    /// Panics if w² is not positive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::whr::WholeHistoryRating;
+    /// let whr = WholeHistoryRating::new(14.0);
+    /// ```
+    pub fn new(w2: f64) -> WholeHistoryRating {
+        assert!(w2 > 0.0, "the variance of the Wiener process must be positive");
+        return WholeHistoryRating {
+            w2,
+            players: Vec::new(),
+        }
+    }
+
+    /// Change the variance of the Wiener process.
+    ///
+    /// # Panics
+    ///
+    /// Panics if w² is not positive.
+    pub fn set_w2(&mut self, w2: f64) {
+        assert!(w2 > 0.0, "the variance of the Wiener process must be positive");
+        self.w2 = w2;
+    }
+
+    /// Returns the variance of the Wiener process.
+    pub fn get_w2(&self) -> f64 {
+        return self.w2;
+    }
+
+    /// Internal method for the Wiener variance between two times, in
+    /// natural units.
+    fn wiener_variance(&self, from: i64, to: i64) -> f64 {
+        return (to - from).abs() as f64 * self.w2 / (SCALE * SCALE);
+    }
+
+    /// Internal method for the index of the day of a player at a time,
+    /// adding the day if needed.
+    fn day(&mut self, player: usize, time: i64) -> usize {
+        while self.players.len() <= player {
+            self.players.push(Vec::new());
+        }
+        let days = &mut self.players[player];
+        match days.binary_search_by_key(&time, |day| day.time) {
+            Ok(index) => return index,
+            Err(index) => {
+                let rating = if index > 0 {
+                    days[index - 1].rating
+                } else if index < days.len() {
+                    days[index].rating
+                } else {
+                    0.0
+                };
+                days.insert(index, Day {
+                    time,
+                    rating,
+                    variance: 0.0,
+                    covariance: 0.0,
+                    games: Vec::new(),
+                });
+                return index;
+            },
+        }
+    }
+
+    /// Add a game between two players.
+    ///
+    /// Players are identified by index, and the score is that of the first
+    /// player. Call `iterate` to update the ratings afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elo::whr::WholeHistoryRating;
+    /// let mut whr = WholeHistoryRating::new(14.0);
+    /// whr.add_game(0, 1, 1, 1.0);
+    /// whr.add_game(1, 0, 5, 0.5);
+    /// whr.iterate(50);
+    /// assert!(whr.rating_at(0, 3).unwrap().rating > 0.0);
+    /// ```
+    pub fn add_game(&mut self, one: usize, two: usize, time: i64, score: f64) {
+        let day_one = self.day(one, time);
+        let day_two = self.day(two, time);
+        self.players[one][day_one].games.push((two, score));
+        self.players[two][day_two].games.push((one, 1.0 - score));
+    }
+
+    /// Internal method for the negative Hessian and gradient of the log
+    /// posterior of a player's history.
+    fn derivatives(&self, player: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        let days = &self.players[player];
+        let mut diagonal = vec![STABILITY; days.len()];
+        let mut off_diagonal = vec![0.0; days.len().saturating_sub(1)];
+        let mut gradient = vec![0.0; days.len()];
+        for (x, day) in days.iter().enumerate() {
+            for &(opponent, score) in &day.games {
+                // The opponent played on the same day, which is found by
+                // time so that adding days never invalidates a game.
+                let opponent_days = &self.players[opponent];
+                let opponent_day = opponent_days
+                    .binary_search_by_key(&day.time, |day| day.time)
+                    .unwrap();
+                let p = probability(day.rating, opponent_days[opponent_day].rating);
+                gradient[x] += score - p;
+                diagonal[x] += p * (1.0 - p);
+            }
+            if x == 0 {
+                // One virtual win and one virtual loss against 0.
+                let p = probability(day.rating, 0.0);
+                gradient[x] += 1.0 - 2.0 * p;
+                diagonal[x] += 2.0 * p * (1.0 - p);
+            }
+            if x + 1 < days.len() {
+                let next = &days[x + 1];
+                let precision = 1.0 / self.wiener_variance(day.time, next.time);
+                let change = (next.rating - day.rating) * precision;
+                gradient[x] += change;
+                gradient[x + 1] -= change;
+                diagonal[x] += precision;
+                diagonal[x + 1] += precision;
+                off_diagonal[x] = -precision;
+            }
+        }
+        return (diagonal, off_diagonal, gradient);
+    }
+
+    /// Internal method for one Newton step on a player's history, followed
+    /// by an update of its covariance.
+    fn update_player(&mut self, player: usize) {
+        let (diagonal, off_diagonal, gradient) = self.derivatives(player);
+        let size = diagonal.len();
+        if size == 0 {
+            return;
+        }
+        // Forward elimination of the tridiagonal system.
+        let mut pivots = diagonal.clone();
+        let mut solution = gradient;
+        for x in 1..size {
+            let factor = off_diagonal[x - 1] / pivots[x - 1];
+            pivots[x] -= factor * off_diagonal[x - 1];
+            solution[x] -= factor * solution[x - 1];
+        }
+        solution[size - 1] /= pivots[size - 1];
+        for x in (0..size - 1).rev() {
+            solution[x] = (solution[x] - off_diagonal[x] * solution[x + 1]) /
+                pivots[x];
+        }
+        // Backward pivots give the diagonal of the inverse.
+        let mut backward = diagonal.clone();
+        for x in (0..size - 1).rev() {
+            backward[x] -= off_diagonal[x] * off_diagonal[x] / backward[x + 1];
+        }
+        let days = &mut self.players[player];
+        for (x, day) in days.iter_mut().enumerate() {
+            day.rating += solution[x];
+            day.variance = 1.0 / (pivots[x] + backward[x] - diagonal[x]);
+        }
+        for x in 0..size - 1 {
+            days[x].covariance = -off_diagonal[x] / pivots[x] *
+                days[x + 1].variance;
+        }
+    }
+
+    /// Run a number of iterations, updating every player in turn.
+    pub fn iterate(&mut self, iterations: usize) {
+        for _ in 0..iterations {
+            for player in 0..self.players.len() {
+                self.update_player(player);
+            }
+        }
+    }
+
+    /// Returns the estimated rating of a player at a time, or `None` if the
+    /// player has not played.
+    ///
+    /// Between games the estimate is interpolated along the Wiener process,
+    /// and before the first or after the last game its uncertainty grows
+    /// with the time since.
+    pub fn rating_at(&self, player: usize, time: i64) -> Option<Estimate> {
+        let days = match self.players.get(player) {
+            Some(days) if !days.is_empty() => days,
+            _ => return None,
+        };
+        let (rating, variance) = match
+            days.binary_search_by_key(&time, |day| day.time) {
+            Ok(index) => (days[index].rating, days[index].variance),
+            Err(0) => {
+                (days[0].rating,
+                 days[0].variance + self.wiener_variance(time, days[0].time))
+            },
+            Err(index) if index == days.len() => {
+                let last = &days[index - 1];
+                (last.rating,
+                 last.variance + self.wiener_variance(last.time, time))
+            },
+            Err(index) => {
+                let before = &days[index - 1];
+                let after = &days[index];
+                let span = (after.time - before.time) as f64;
+                let weight_before = (after.time - time) as f64 / span;
+                let weight_after = (time - before.time) as f64 / span;
+                let rating = weight_before * before.rating +
+                    weight_after * after.rating;
+                let variance = weight_before * weight_after *
+                    self.wiener_variance(before.time, after.time) +
+                    weight_before * weight_before * before.variance +
+                    2.0 * weight_before * weight_after * before.covariance +
+                    weight_after * weight_after * after.variance;
+                (rating, variance)
+            },
+        };
+        return Some(Estimate {
+            rating: rating * SCALE,
+            deviation: variance.sqrt() * SCALE,
+        });
+    }
+
+    /// Returns the estimated rating of a player at the time of every game.
+    pub fn history(&self, player: usize) -> Vec<(i64, Estimate)> {
+        return match self.players.get(player) {
+            Some(days) => days.iter()
+                .map(|day| (day.time, Estimate {
+                    rating: day.rating * SCALE,
+                    deviation: day.variance.sqrt() * SCALE,
+                }))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric() {
+        let mut whr = WholeHistoryRating::new(100.0);
+        for time in 0..10 {
+            whr.add_game(0, 1, time * 10, if time % 3 == 0 { 0.0 } else { 1.0 });
+        }
+        whr.iterate(100);
+        for (a, b) in whr.history(0).iter().zip(whr.history(1).iter()) {
+            assert_eq!(a.0, b.0);
+            assert!(a.1.rating > 0.0);
+            assert!((a.1.rating + b.1.rating).abs() < 1e-6);
+            assert!((a.1.deviation - b.1.deviation).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn converges_to_posterior_mode() {
+        let mut whr = WholeHistoryRating::new(50.0);
+        whr.add_game(0, 1, 1, 1.0);
+        whr.add_game(1, 2, 2, 1.0);
+        whr.add_game(2, 0, 4, 0.5);
+        whr.add_game(1, 0, 8, 0.0);
+        whr.iterate(200);
+        for player in 0..3 {
+            let (_, _, gradient) = whr.derivatives(player);
+            assert!(gradient.iter().all(|g| g.abs() < 1e-9));
+        }
+        // Days can be added out of order.
+        whr.add_game(2, 1, 0, 1.0);
+        whr.iterate(200);
+        assert_eq!(vec![0, 2, 4], whr.history(2).iter()
+                   .map(|&(time, _)| time)
+                   .collect::<Vec<i64>>());
+        for player in 0..3 {
+            let (_, _, gradient) = whr.derivatives(player);
+            assert!(gradient.iter().all(|g| g.abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn interpolation() {
+        let mut whr = WholeHistoryRating::new(20.0);
+        whr.add_game(0, 1, 0, 1.0);
+        whr.add_game(0, 1, 100, 0.0);
+        whr.iterate(100);
+        let start = whr.rating_at(0, 0).unwrap();
+        let end = whr.rating_at(0, 100).unwrap();
+        let middle = whr.rating_at(0, 50).unwrap();
+        assert!((middle.rating - (start.rating + end.rating) / 2.0).abs() < 1e-9);
+        assert!(middle.deviation > start.deviation);
+        let later = whr.rating_at(0, 200).unwrap();
+        assert_eq!(end.rating, later.rating);
+        let variance = end.deviation * end.deviation + 100.0 * 20.0;
+        assert!((later.deviation - variance.sqrt()).abs() < 1e-9);
+        assert_eq!(None, whr.rating_at(2, 0));
+    }
+
+    #[test]
+    fn insertion_order() {
+        let games = [(0, 1, 3, 1.0), (1, 2, 1, 0.5), (2, 0, 2, 1.0), (0, 2, 0, 0.0)];
+        let mut forward = WholeHistoryRating::new(30.0);
+        let mut backward = WholeHistoryRating::new(30.0);
+        for &(one, two, time, score) in games.iter() {
+            forward.add_game(one, two, time, score);
+        }
+        for &(one, two, time, score) in games.iter().rev() {
+            backward.add_game(one, two, time, score);
+        }
+        forward.iterate(100);
+        backward.iterate(100);
+        for player in 0..3 {
+            for (a, b) in forward.history(player).iter()
+                .zip(backward.history(player).iter()) {
+                assert_eq!(a.0, b.0);
+                assert!((a.1.rating - b.1.rating).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the variance of the Wiener process must be positive")]
+    fn zero_variance() {
+        WholeHistoryRating::new(0.0);
+    }
+}